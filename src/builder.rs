@@ -0,0 +1,77 @@
+use crate::{
+    AutoCalibration, Axis, Rounding, Sample,
+    error::{AxisError, check_center, check_range},
+};
+
+/// Validating builder for [Axis], see [Axis::builder]
+#[derive(Clone, Copy)]
+pub struct AxisBuilder<S: Sample = u16> {
+    min: S,
+    max: S,
+    center: Option<S>,
+    reversed: bool,
+    step_filter_factor: S,
+    rounding: Rounding,
+    auto_calibration: Option<AutoCalibration<S>>,
+}
+
+impl<S: Sample> AxisBuilder<S> {
+    pub(crate) fn new(min: S, max: S) -> Self {
+        Self {
+            min,
+            max,
+            center: None,
+            reversed: false,
+            step_filter_factor: S::default(),
+            rounding: Rounding::Floor,
+            auto_calibration: None,
+        }
+    }
+
+    /// Makes the axis center-calibrated, see [Axis::new_centered]
+    pub fn center(mut self, center: S) -> Self {
+        self.center = Some(center);
+        self
+    }
+
+    pub fn reversed(mut self, reversed: bool) -> Self {
+        self.reversed = reversed;
+        self
+    }
+
+    pub fn step_filter_factor(mut self, factor: S) -> Self {
+        self.step_filter_factor = factor;
+        self
+    }
+
+    pub fn rounding(mut self, rounding: Rounding) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    pub fn auto_calibration(mut self, startup_samples: u16, min_travel: S) -> Self {
+        self.auto_calibration = Some(AutoCalibration::new(startup_samples, min_travel));
+        self
+    }
+
+    pub fn build(self) -> Result<Axis<S>, AxisError> {
+        check_range(self.min, self.max)?;
+
+        let mut axis = match self.center {
+            Some(center) => {
+                check_center(self.min, center, self.max)?;
+                Axis::new_centered(self.min, center, self.max, self.reversed)
+            }
+            None => Axis::new(self.min, self.max, self.reversed),
+        };
+
+        if self.step_filter_factor < S::default() {
+            return Err(AxisError::InvalidFactor);
+        }
+
+        axis.step_filter_factor = self.step_filter_factor;
+        axis.rounding = self.rounding;
+        axis.auto_calibration = self.auto_calibration;
+        Ok(axis)
+    }
+}
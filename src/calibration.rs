@@ -0,0 +1,450 @@
+use crate::{Axis, AxisError, DynEffect, Sample};
+use core::fmt;
+
+/// Runtime calibration that learns axis range from observed travel
+#[derive(Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AutoCalibration<S: Sample = u16> {
+    /// Number of samples collected on startup before learned range can be trusted
+    pub startup_samples: u16,
+    /// Minimal learned travel (`max - min`) before learned range can be trusted
+    pub min_travel: S,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    learned: Option<(S, S)>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    samples: u16,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    frozen: bool,
+}
+
+impl<S: Sample> AutoCalibration<S> {
+    pub fn new(startup_samples: u16, min_travel: S) -> Self {
+        Self {
+            startup_samples,
+            min_travel,
+            learned: None,
+            samples: 0,
+            frozen: false,
+        }
+    }
+
+    /// Seeds learned range with `min..=max` persisted earlier, e.g. from [AutoCalibration::learned],
+    /// and marks the startup window as over. The range is trusted right away if it is at least
+    /// [AutoCalibration::min_travel] wide, and is applied to the axis on its next update
+    pub fn with_learned(mut self, min: S, max: S) -> Self {
+        self.learned = Some((min.min(max), min.max(max)));
+        self.samples = self.samples.max(self.startup_samples);
+        self
+    }
+
+    /// Widens learned range with `value`. Does nothing if calibration is frozen
+    pub fn observe(&mut self, value: S) {
+        if self.frozen {
+            return;
+        }
+
+        self.samples = self.samples.saturating_add(1);
+        self.learned = match self.learned {
+            Some((min, max)) => Some((min.min(value), max.max(value))),
+            None => Some((value, value)),
+        };
+    }
+
+    /// Learned `(min, max)` range, trusted or not. Can be persisted by firmware
+    pub fn learned(&self) -> Option<(S, S)> {
+        self.learned
+    }
+
+    /// Returns `true` if startup window is over and learned travel is wide enough
+    pub fn is_trusted(&self) -> bool {
+        match self.learned {
+            Some((min, max)) => {
+                self.samples >= self.startup_samples
+                    && max.to_i64() - min.to_i64() >= self.min_travel.to_i64()
+            }
+            None => false,
+        }
+    }
+
+    /// Learned range if it is trusted
+    pub fn trusted_range(&self) -> Option<(S, S)> {
+        if self.is_trusted() {
+            self.learned
+        } else {
+            None
+        }
+    }
+
+    /// Stops learning, current learned range stays as is. [Axis] stops applying it as well, so
+    /// `min` and `max` set on a frozen axis are kept
+    pub fn freeze(&mut self) {
+        self.frozen = true;
+    }
+
+    /// Resumes learning
+    pub fn unfreeze(&mut self) {
+        self.frozen = false;
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    /// Forgets learned range and restarts startup window
+    pub fn reset(&mut self) {
+        self.learned = None;
+        self.samples = 0;
+    }
+}
+
+/// Current [Calibration] blob format version
+pub const CALIBRATION_VERSION: u8 = 1;
+/// Number of effect parameters stored in [Calibration]
+pub const CALIBRATION_PARAMS: usize = 8;
+/// Size of serialized [Calibration] in bytes
+pub const CALIBRATION_SIZE: usize = 14 + CALIBRATION_PARAMS * 4 + 2;
+
+const MAGIC: [u8; 2] = *b"AX";
+const FLAG_REVERSED: u8 = 1 << 0;
+const FLAG_CENTER: u8 = 1 << 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CalibrationError {
+    /// Buffer does not contain calibration blob
+    BadMagic,
+    /// Blob was written by other format version
+    UnsupportedVersion(u8),
+    /// Checksum does not match, data is corrupt
+    Checksum,
+    /// Reserved bytes are not zero, blob was written by incompatible firmware
+    Reserved,
+}
+
+impl fmt::Display for CalibrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalibrationError::BadMagic => write!(f, "buffer does not contain calibration"),
+            CalibrationError::UnsupportedVersion(version) => {
+                write!(f, "unsupported calibration version {version}")
+            }
+            CalibrationError::Checksum => write!(f, "calibration checksum mismatch"),
+            CalibrationError::Reserved => write!(f, "calibration reserved bytes are not zero"),
+        }
+    }
+}
+
+impl core::error::Error for CalibrationError {}
+
+/// Calibration of `u16` axis that can be stored in EEPROM/flash as fixed-size blob.
+///
+/// Layout (little-endian): magic `AX`, version, flags, min, center, max, step filter factor,
+/// two reserved bytes that must be zero, effect parameters as `f32` and CRC-16/CCITT of all
+/// preceding bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Calibration {
+    pub min: u16,
+    pub center: Option<u16>,
+    pub max: u16,
+    pub reversed: bool,
+    pub step_filter_factor: u16,
+    /// Effect parameters, their meaning is up to firmware. [Calibration::store_effect_params]
+    /// and [Calibration::apply_effect_params] keep them in chain descriptor order
+    pub effect_params: [f32; CALIBRATION_PARAMS],
+}
+
+impl Calibration {
+    pub fn from_axis<const N: usize>(axis: &Axis<u16, N>) -> Self {
+        Self {
+            min: axis.min,
+            center: axis.center,
+            max: axis.max,
+            reversed: axis.reversed,
+            step_filter_factor: axis.step_filter_factor,
+            effect_params: [0.0; CALIBRATION_PARAMS],
+        }
+    }
+
+    /// Builds validated axis from calibration, rejects bad data loaded from storage
+    pub fn to_axis(&self) -> Result<Axis, AxisError> {
+        let mut builder = Axis::builder(self.min, self.max)
+            .reversed(self.reversed)
+            .step_filter_factor(self.step_filter_factor);
+        if let Some(center) = self.center {
+            builder = builder.center(center);
+        }
+        builder.build()
+    }
+
+    /// Applies calibration to `axis`
+    pub fn apply<const N: usize>(&self, axis: &mut Axis<u16, N>) {
+        axis.min = self.min;
+        axis.center = self.center;
+        axis.max = self.max;
+        axis.reversed = self.reversed;
+        axis.step_filter_factor = self.step_filter_factor;
+    }
+
+    /// Fills [Calibration::effect_params] with parameters of `chain` effects, effect by effect in
+    /// [crate::EffectDescriptor] order. Effects without descriptor are skipped, unused slots are
+    /// zeroed and parameters past [CALIBRATION_PARAMS] are dropped. Returns number of stored
+    /// parameters
+    pub fn store_effect_params<'a, const N: usize>(
+        &mut self,
+        chain: impl IntoIterator<Item = &'a DynEffect<u16, N>>,
+    ) -> usize {
+        self.effect_params = [0.0; CALIBRATION_PARAMS];
+        let mut slots = self.effect_params.iter_mut();
+        let mut stored = 0;
+        for effect in chain {
+            let Some(descriptor) = effect.descriptor() else {
+                continue;
+            };
+            for param in descriptor.params {
+                let Some(slot) = slots.next() else {
+                    return stored;
+                };
+                *slot = effect.param(param.name).unwrap_or(param.default);
+                stored += 1;
+            }
+        }
+        stored
+    }
+
+    /// Sets parameters of `chain` effects from [Calibration::effect_params] in the order written
+    /// by [Calibration::store_effect_params]. Parameters past [CALIBRATION_PARAMS] are left as is.
+    /// Stops at the first value rejected by [DynEffect::set_param]
+    pub fn apply_effect_params<'a, const N: usize>(
+        &self,
+        chain: impl IntoIterator<Item = &'a mut DynEffect<u16, N>>,
+    ) -> Result<(), AxisError> {
+        let mut values = self.effect_params.iter();
+        for effect in chain {
+            let Some(descriptor) = effect.descriptor() else {
+                continue;
+            };
+            for param in descriptor.params {
+                let Some(value) = values.next() else {
+                    return Ok(());
+                };
+                effect.set_param(param.name, *value)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> [u8; CALIBRATION_SIZE] {
+        let mut buf = [0; CALIBRATION_SIZE];
+        let mut flags = 0;
+        if self.reversed {
+            flags |= FLAG_REVERSED;
+        }
+        if self.center.is_some() {
+            flags |= FLAG_CENTER;
+        }
+
+        buf[0..2].copy_from_slice(&MAGIC);
+        buf[2] = CALIBRATION_VERSION;
+        buf[3] = flags;
+        buf[4..6].copy_from_slice(&self.min.to_le_bytes());
+        buf[6..8].copy_from_slice(&self.center.unwrap_or_default().to_le_bytes());
+        buf[8..10].copy_from_slice(&self.max.to_le_bytes());
+        buf[10..12].copy_from_slice(&self.step_filter_factor.to_le_bytes());
+        for (i, param) in self.effect_params.iter().enumerate() {
+            let offset = 14 + i * 4;
+            buf[offset..offset + 4].copy_from_slice(&param.to_le_bytes());
+        }
+
+        let crc_offset = CALIBRATION_SIZE - 2;
+        let crc = crc16(&buf[..crc_offset]);
+        buf[crc_offset..].copy_from_slice(&crc.to_le_bytes());
+        buf
+    }
+
+    pub fn from_bytes(buf: &[u8; CALIBRATION_SIZE]) -> Result<Self, CalibrationError> {
+        if buf[0..2] != MAGIC {
+            return Err(CalibrationError::BadMagic);
+        }
+
+        // Checksum goes first, so corrupted version byte is not reported as other version
+        let crc_offset = CALIBRATION_SIZE - 2;
+        if crc16(&buf[..crc_offset]) != u16::from_le_bytes([buf[crc_offset], buf[crc_offset + 1]]) {
+            return Err(CalibrationError::Checksum);
+        }
+
+        if buf[2] != CALIBRATION_VERSION {
+            return Err(CalibrationError::UnsupportedVersion(buf[2]));
+        }
+
+        if buf[12..14] != [0; 2] {
+            return Err(CalibrationError::Reserved);
+        }
+
+        let read_u16 = |offset: usize| u16::from_le_bytes([buf[offset], buf[offset + 1]]);
+        let flags = buf[3];
+        let mut effect_params = [0.0; CALIBRATION_PARAMS];
+        for (i, param) in effect_params.iter_mut().enumerate() {
+            let offset = 14 + i * 4;
+            *param = f32::from_le_bytes([
+                buf[offset],
+                buf[offset + 1],
+                buf[offset + 2],
+                buf[offset + 3],
+            ]);
+        }
+
+        Ok(Self {
+            min: read_u16(4),
+            center: (flags & FLAG_CENTER != 0).then(|| read_u16(6)),
+            max: read_u16(8),
+            reversed: flags & FLAG_REVERSED != 0,
+            step_filter_factor: read_u16(10),
+            effect_params,
+        })
+    }
+}
+
+/// CRC-16/CCITT-FALSE
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for byte in data {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Pipeline,
+        effects::{Deadzone, Expo, Lerp},
+    };
+
+    fn calibration() -> Calibration {
+        Calibration {
+            min: 12,
+            center: Some(2040),
+            max: 4000,
+            reversed: true,
+            step_filter_factor: 3,
+            effect_params: [0.5, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.25],
+        }
+    }
+
+    #[test]
+    fn crc16_check_value() {
+        assert_eq!(crc16(b"123456789"), 0x29B1);
+    }
+
+    #[test]
+    fn blob_roundtrip() {
+        let bytes = calibration().to_bytes();
+        assert_eq!(Calibration::from_bytes(&bytes), Ok(calibration()));
+
+        let mut axis = Axis::new(0, 1, false);
+        calibration().apply(&mut axis);
+        assert_eq!(Calibration::from_axis(&axis).center, Some(2040));
+        assert_eq!(calibration().to_axis().unwrap().center, Some(2040));
+
+        let inverted = Calibration {
+            min: 4000,
+            max: 12,
+            ..calibration()
+        };
+        assert_eq!(inverted.to_axis().err(), Some(AxisError::InvertedBounds));
+    }
+
+    #[test]
+    fn chain_effect_params() {
+        let mut pipeline: Pipeline = Pipeline::new();
+        pipeline.push(Lerp::new(0.25)).unwrap();
+        pipeline.push(Deadzone::new(0, 1000, 20, 30)).unwrap();
+        pipeline.push(Expo::new(0, 1000, 0.5)).unwrap();
+
+        let mut calibration = calibration();
+        assert_eq!(
+            calibration.store_effect_params(&pipeline),
+            CALIBRATION_PARAMS
+        );
+        assert_eq!(
+            calibration.effect_params,
+            [0.25, 20.0, 30.0, 500.0, 0.0, 1000.0, 0.5, 0.5]
+        );
+
+        let mut loaded = Calibration::from_bytes(&calibration.to_bytes()).unwrap();
+        loaded.effect_params[0] = 0.75;
+        loaded.effect_params[1] = 50.0;
+        loaded.effect_params[7] = -0.5;
+        loaded.apply_effect_params(&mut pipeline).unwrap();
+        let lerp = pipeline.get(0).unwrap();
+        assert_eq!(lerp.param("factor"), Some(0.75));
+        let deadzone = pipeline.get(1).unwrap();
+        assert_eq!(deadzone.param("inner"), Some(50.0));
+        let expo = pipeline.get(2).unwrap();
+        assert_eq!(expo.param("low"), Some(-0.5));
+        // Parameters that did not fit keep their values
+        assert_eq!(expo.param("high"), Some(0.5));
+        assert_eq!(expo.param("max"), Some(1000.0));
+
+        let mut short = Pipeline::<u16, 1>::new();
+        short.push(Lerp::new(0.5)).unwrap();
+        assert_eq!(calibration.store_effect_params(&short), 1);
+        assert_eq!(
+            calibration.effect_params,
+            [0.5, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]
+        );
+
+        calibration.effect_params[0] = 2.0;
+        assert_eq!(
+            calibration.apply_effect_params(&mut short),
+            Err(AxisError::InvalidFactor)
+        );
+    }
+
+    #[test]
+    fn blob_rejects_bad_data() {
+        let mut bytes = calibration().to_bytes();
+        bytes[5] ^= 0x10;
+        assert_eq!(
+            Calibration::from_bytes(&bytes),
+            Err(CalibrationError::Checksum)
+        );
+
+        let mut bytes = calibration().to_bytes();
+        bytes[2] = 0;
+        assert_eq!(
+            Calibration::from_bytes(&bytes),
+            Err(CalibrationError::Checksum)
+        );
+
+        let crc_offset = CALIBRATION_SIZE - 2;
+        let crc = crc16(&bytes[..crc_offset]);
+        bytes[crc_offset..].copy_from_slice(&crc.to_le_bytes());
+        assert_eq!(
+            Calibration::from_bytes(&bytes),
+            Err(CalibrationError::UnsupportedVersion(0))
+        );
+
+        let mut bytes = calibration().to_bytes();
+        bytes[13] = 1;
+        let crc = crc16(&bytes[..crc_offset]);
+        bytes[crc_offset..].copy_from_slice(&crc.to_le_bytes());
+        assert_eq!(
+            Calibration::from_bytes(&bytes),
+            Err(CalibrationError::Reserved)
+        );
+
+        assert_eq!(
+            Calibration::from_bytes(&[0xFF; CALIBRATION_SIZE]),
+            Err(CalibrationError::BadMagic)
+        );
+    }
+}
@@ -0,0 +1,255 @@
+use crate::{AxisError, DynEffect, Effect, EffectDescriptor, MAX_EFFECT_SIZE, Sample};
+
+/// Effect chain accepted by [crate::Axis::update]. Implemented for iterators over
+/// [DynEffect]s and for statically composed [Chain]s
+pub trait EffectChain<S: Sample = u16, const N: usize = MAX_EFFECT_SIZE> {
+    fn apply(self, input: S) -> S;
+}
+
+impl<'a, S: Sample, const N: usize, I> EffectChain<S, N> for I
+where
+    I: IntoIterator<Item = &'a mut DynEffect<S, N>>,
+{
+    fn apply(self, input: S) -> S {
+        let mut value = input;
+        for filter in self {
+            value = filter.update(value);
+        }
+        value
+    }
+}
+
+/// Statically composed effect chain. Effects are applied in order without dynamic dispatch,
+/// so the whole chain can be inlined. Several effects are chained with a tuple:
+///
+/// ```
+/// use axis::{Axis, Chain, effects::{Deadzone, Expo, Lerp}};
+///
+/// let mut chain = Chain::new((
+///     Deadzone::new(0, 1000, 20, 0),
+///     Expo::new(0, 1000, 0.3),
+///     Lerp::new(0.5),
+/// ));
+/// let mut axis = Axis::new(0, 1000, false);
+/// axis.update(500, &mut chain);
+/// ```
+///
+/// Unlike [DynEffect], the effects have no size limits. Wrap effects in [Bypass] to switch
+/// them off at runtime.
+#[derive(Clone, Copy, Default)]
+pub struct Chain<E>(E);
+
+impl<E> Chain<E> {
+    pub fn new(effects: E) -> Self {
+        Self(effects)
+    }
+
+    pub fn effects(&self) -> &E {
+        &self.0
+    }
+
+    pub fn effects_mut(&mut self) -> &mut E {
+        &mut self.0
+    }
+}
+
+impl<S: Sample, E: Effect<S>> Effect<S> for Chain<E> {
+    fn update(&mut self, input: S) -> S {
+        self.0.update(input)
+    }
+
+    fn reset(&mut self) {
+        self.0.reset()
+    }
+}
+
+impl<S: Sample, const N: usize, E: Effect<S>> EffectChain<S, N> for &mut Chain<E> {
+    fn apply(self, input: S) -> S {
+        self.0.update(input)
+    }
+}
+
+/// Makes the wrapped effect bypassable, disabled effect passes input through unchanged.
+/// Counterpart of [DynEffect::set_enabled] for statically composed [Chain]s
+#[derive(Clone, Copy, Default)]
+pub struct Bypass<E> {
+    effect: E,
+    enabled: bool,
+}
+
+impl<E> Bypass<E> {
+    /// Wraps enabled `effect`
+    pub fn new(effect: E) -> Self {
+        Self {
+            effect,
+            enabled: true,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enables or bypasses the effect
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn effect(&self) -> &E {
+        &self.effect
+    }
+
+    pub fn effect_mut(&mut self) -> &mut E {
+        &mut self.effect
+    }
+}
+
+impl<S: Sample, E: Effect<S>> Effect<S> for Bypass<E> {
+    fn update(&mut self, input: S) -> S {
+        if self.enabled {
+            self.effect.update(input)
+        } else {
+            input
+        }
+    }
+
+    fn reset(&mut self) {
+        self.effect.reset()
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn param(&self, name: &str) -> Option<f32> {
+        self.effect.param(name)
+    }
+
+    fn set_param(&mut self, name: &str, value: f32) -> Result<(), AxisError> {
+        self.effect.set_param(name, value)
+    }
+
+    fn param_sample(&self, name: &str) -> Option<S> {
+        self.effect.param_sample(name)
+    }
+
+    fn set_param_sample(&mut self, name: &str, value: S) -> Result<(), AxisError> {
+        self.effect.set_param_sample(name, value)
+    }
+
+    fn descriptor(&self) -> Option<&'static EffectDescriptor> {
+        self.effect.descriptor()
+    }
+}
+
+macro_rules! impl_tuple_effect {
+    ($($name:ident),+) => {
+        impl<S: Sample, $($name: Effect<S>),+> Effect<S> for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn update(&mut self, input: S) -> S {
+                let ($($name,)+) = self;
+                let value = input;
+                $(let value = $name.update(value);)+
+                value
+            }
+
+            #[allow(non_snake_case)]
+            fn reset(&mut self) {
+                let ($($name,)+) = self;
+                $($name.reset();)+
+            }
+        }
+    };
+}
+
+impl_tuple_effect!(A);
+impl_tuple_effect!(A, B);
+impl_tuple_effect!(A, B, C);
+impl_tuple_effect!(A, B, C, D);
+impl_tuple_effect!(A, B, C, D, E);
+impl_tuple_effect!(A, B, C, D, E, F);
+impl_tuple_effect!(A, B, C, D, E, F, G);
+impl_tuple_effect!(A, B, C, D, E, F, G, H);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Axis,
+        effects::{Curve, Deadzone, Expo, Lerp, Smooth},
+    };
+
+    #[test]
+    fn matches_dyn_chain() {
+        let mut chain = Chain::new((
+            Deadzone::new(0, 1000, 20, 10),
+            Expo::new(0, 1000, 0.4),
+            Lerp::new(0.5),
+        ));
+        let mut effects: [DynEffect; 3] = [
+            Deadzone::new(0, 1000, 20, 10).into(),
+            Expo::new(0, 1000, 0.4).into(),
+            Lerp::new(0.5).into(),
+        ];
+
+        let mut static_axis = Axis::new(0, 1000, false);
+        let mut dyn_axis = Axis::new(0, 1000, false);
+        for value in (0..=1000).step_by(37).chain((0..=1000).rev().step_by(53)) {
+            static_axis.update(value, &mut chain);
+            dyn_axis.update(value, effects.iter_mut());
+            assert_eq!(static_axis.output(0, 1000), dyn_axis.output(0, 1000));
+        }
+    }
+
+    #[test]
+    fn nested_and_mixed_chains() {
+        static CURVE: Curve<3> = Curve::new([(0, 0), (100, 50), (200, 200)]);
+
+        let mut chain = Chain::new((
+            (Smooth::new(1000u16),),
+            DynEffect::<u16>::from(&CURVE),
+            Curve::new([(0, 200), (200, 0)]),
+        ));
+        let mut axis = Axis::new(0, 200, false);
+        axis.update(100, &mut chain);
+        assert_eq!(axis.output(0, 200), 150);
+        axis.update(100, []);
+        assert_eq!(axis.output(0, 200), 100);
+    }
+
+    #[test]
+    fn bypass() {
+        let mut chain = Chain::new((
+            Bypass::new(Lerp::new(0.5)),
+            Bypass::new(Smooth::new(300u16)),
+        ));
+        let mut axis = Axis::new(0, 1000, false);
+        axis.update(1000, &mut chain);
+        assert_eq!(axis.output(0, 1000), 300);
+
+        chain.effects_mut().1.set_enabled(false);
+        axis.update(0, &mut chain);
+        assert_eq!(axis.output(0, 1000), 500);
+
+        let (lerp, _) = chain.effects_mut();
+        lerp.set_enabled(false);
+        assert!(!lerp.is_enabled());
+        Effect::<u16>::set_param(lerp, "factor", 0.25).unwrap();
+        assert_eq!(Effect::<u16>::param(lerp.effect(), "factor"), Some(0.25));
+        assert_eq!(Effect::<u16>::descriptor(lerp).unwrap().name, "lerp");
+        axis.update(800, &mut chain);
+        assert_eq!(axis.output(0, 1000), 800);
+
+        fn enabled<E: Effect<u16>>(effect: &E) -> bool {
+            effect.is_enabled()
+        }
+        let (lerp, smooth) = chain.effects_mut();
+        assert!(!enabled(lerp));
+        smooth.set_enabled(true);
+        assert!(enabled(smooth));
+        assert!(enabled(smooth.effect()));
+        let mut dyn_lerp = DynEffect::<u16>::from(Lerp::new(0.5));
+        dyn_lerp.set_enabled(false);
+        assert!(!enabled(&dyn_lerp));
+    }
+}
@@ -0,0 +1,92 @@
+use crate::Sample;
+
+/// Unit of effect parameter value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParamUnit {
+    /// Dimensionless factor or ratio
+    Factor,
+    /// Raw sample value, same units as axis input
+    Sample,
+    /// Non-negative distance between sample values, e.g. zone width
+    Distance,
+    /// Frequency, e.g. filter cutoff of [crate::effects::OneEuro]
+    Hertz,
+    /// Time interval, e.g. sample period of [crate::effects::OneEuro]
+    Seconds,
+}
+
+impl ParamUnit {
+    /// Unit symbol for display, empty for dimensionless and sample values
+    pub fn symbol(self) -> &'static str {
+        match self {
+            ParamUnit::Factor | ParamUnit::Sample | ParamUnit::Distance => "",
+            ParamUnit::Hertz => "Hz",
+            ParamUnit::Seconds => "s",
+        }
+    }
+
+    /// Whether the value is a sample value or a distance between them, see
+    /// [crate::Effect::param_sample]
+    pub fn is_sample(self) -> bool {
+        matches!(self, ParamUnit::Sample | ParamUnit::Distance)
+    }
+}
+
+/// Numeric effect parameter, read and written with [crate::Effect::param] and
+/// [crate::Effect::set_param]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParamDescriptor {
+    pub name: &'static str,
+    pub min: f32,
+    pub max: f32,
+    pub default: f32,
+    pub unit: ParamUnit,
+}
+
+impl ParamDescriptor {
+    pub const fn new(
+        name: &'static str,
+        min: f32,
+        max: f32,
+        default: f32,
+        unit: ParamUnit,
+    ) -> Self {
+        Self {
+            name,
+            min,
+            max,
+            default,
+            unit,
+        }
+    }
+
+    /// Sample value parameter spanning the whole range of `S`
+    pub const fn sample<S: Sample>(name: &'static str, default: f32) -> Self {
+        Self::new(name, S::MIN_F32, S::MAX_F32, default, ParamUnit::Sample)
+    }
+
+    /// Non-negative sample distance parameter, e.g. deadzone width
+    pub const fn distance<S: Sample>(name: &'static str, default: f32) -> Self {
+        Self::new(name, 0.0, S::MAX_F32, default, ParamUnit::Distance)
+    }
+
+    /// Midpoint of the sample range, default center of ranged effects
+    pub(crate) const fn midpoint<S: Sample>(name: &'static str) -> Self {
+        let mid = S::MIN_F32 + ((S::MAX_F32 - S::MIN_F32) / 2.0) as i64 as f32;
+        Self::sample::<S>(name, mid)
+    }
+}
+
+/// Type name and parameters of an effect. Lets configuration UIs and protocols list and edit
+/// parameters of any [crate::DynEffect] without knowing its concrete type
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EffectDescriptor {
+    pub name: &'static str,
+    pub params: &'static [ParamDescriptor],
+}
+
+impl EffectDescriptor {
+    pub fn param(&self, name: &str) -> Option<&'static ParamDescriptor> {
+        self.params.iter().find(|param| param.name == name)
+    }
+}
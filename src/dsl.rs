@@ -0,0 +1,533 @@
+//! Compact text form of effect chains:
+//!
+//! ```text
+//! deadzone(3%) | expo(0.3, center=500) | !lerp(0.5)
+//! ```
+//!
+//! Effects are looked up by [crate::EffectDescriptor] name in the [Registry]. Arguments are
+//! positional in descriptor order, followed by named ones. `%` means percent of the effect
+//! `min..max` range for sample parameters (e.g. `inner=3%` of deadzone) and percent of the
+//! parameter range for other ones. `!` marks a bypassed effect. Parentheses can be omitted for
+//! default parameters.
+
+use crate::{
+    AxisError, DynEffect, ParamDescriptor, ParamUnit, Pipeline, Registry, Sample,
+    registry::{EffectEntry, RegistryError},
+};
+use core::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// Expected character is missing
+    Expected(char),
+    /// Expected effect or parameter name
+    ExpectedName,
+    /// Expected number
+    InvalidNumber,
+    UnknownEffect,
+    UnknownParam,
+    /// More positional arguments than effect parameters
+    TooManyArgs,
+    /// Positional argument follows named one
+    PositionalAfterNamed,
+    /// Effect rejected the value or pipeline is full
+    Rejected(AxisError),
+}
+
+/// Parse error at byte `position` of the text
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub position: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ParseErrorKind::Expected(c) => write!(f, "expected '{c}'")?,
+            ParseErrorKind::ExpectedName => write!(f, "expected name")?,
+            ParseErrorKind::InvalidNumber => write!(f, "invalid number")?,
+            ParseErrorKind::UnknownEffect => write!(f, "unknown effect")?,
+            ParseErrorKind::UnknownParam => write!(f, "unknown parameter")?,
+            ParseErrorKind::TooManyArgs => write!(f, "too many arguments")?,
+            ParseErrorKind::PositionalAfterNamed => {
+                write!(f, "positional argument after named one")?
+            }
+            ParseErrorKind::Rejected(error) => write!(f, "{error}")?,
+        }
+        write!(f, " at {}", self.position)
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// Pass over effect arguments, see [Parser::effect]
+#[derive(Clone, Copy)]
+enum Pass {
+    /// Applies plain values
+    Values,
+    /// Applies `%` values, relative to `min..max` of the effect if it has them
+    Percents(Option<(f32, f32)>),
+}
+
+/// Resolves `value` percent of `param`. Sample parameters are relative to the effect `range`,
+/// other ones to the descriptor range
+fn percent(param: &ParamDescriptor, range: Option<(f32, f32)>, value: f32) -> f32 {
+    let (min, max) = match param.unit {
+        ParamUnit::Sample | ParamUnit::Distance => range.unwrap_or((param.min, param.max)),
+        _ => (param.min, param.max),
+    };
+    let offset = if param.unit == ParamUnit::Distance {
+        0.0
+    } else {
+        min
+    };
+    offset + (max - min) * value / 100.0
+}
+
+struct Parser<'t> {
+    text: &'t str,
+    pos: usize,
+}
+
+impl<'t> Parser<'t> {
+    fn error(&self, position: usize, kind: ParseErrorKind) -> ParseError {
+        ParseError { position, kind }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        self.skip_whitespace();
+        let found = self.peek() == Some(c);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), ParseError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(self.pos, ParseErrorKind::Expected(c as char)))
+        }
+    }
+
+    /// Consumes the longest run of bytes matching `f`
+    fn take_while(&mut self, f: impl Fn(u8) -> bool) -> &'t str {
+        let start = self.pos;
+        while self.peek().is_some_and(&f) {
+            self.pos += 1;
+        }
+        &self.text[start..self.pos]
+    }
+
+    fn name(&mut self) -> Result<&'t str, ParseError> {
+        self.skip_whitespace();
+        if !self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == b'_')
+        {
+            return Err(self.error(self.pos, ParseErrorKind::ExpectedName));
+        }
+        Ok(self.take_while(|c| c.is_ascii_alphanumeric() || c == b'_'))
+    }
+
+    fn number(&mut self) -> Result<f32, ParseError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        self.take_while(|c| c.is_ascii_digit() || matches!(c, b'+' | b'-' | b'.' | b'e' | b'E'))
+            .parse()
+            .map_err(|_| self.error(start, ParseErrorKind::InvalidNumber))
+    }
+
+    fn effect<S: Sample, const N: usize>(
+        &mut self,
+        registry: &Registry<'_, S, N>,
+    ) -> Result<DynEffect<S, N>, ParseError> {
+        let enabled = !self.eat(b'!');
+        self.skip_whitespace();
+        let start = self.pos;
+        let name = self.name()?;
+        let entry = registry
+            .by_name(name)
+            .ok_or(self.error(start, ParseErrorKind::UnknownEffect))?;
+
+        let mut effect = (entry.create)();
+        effect.set_enabled(enabled);
+        if self.eat(b'(') && !self.eat(b')') {
+            // `%` arguments are relative to the range set by other arguments,
+            // so they are applied in a second pass
+            let start = self.pos;
+            self.arguments(entry, &mut effect, Pass::Values)?;
+            let range = effect.param("min").zip(effect.param("max"));
+            self.pos = start;
+            self.arguments(entry, &mut effect, Pass::Percents(range))?;
+        }
+        Ok(effect)
+    }
+
+    fn arguments<S: Sample, const N: usize>(
+        &mut self,
+        entry: &EffectEntry<S, N>,
+        effect: &mut DynEffect<S, N>,
+        pass: Pass,
+    ) -> Result<(), ParseError> {
+        let params = entry.descriptor.params;
+        let mut index = 0;
+        let mut named = false;
+        loop {
+            self.skip_whitespace();
+            let start = self.pos;
+            let param = if self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+                let name = self.name()?;
+                self.expect(b'=')?;
+                named = true;
+                entry
+                    .descriptor
+                    .param(name)
+                    .ok_or(self.error(start, ParseErrorKind::UnknownParam))?
+            } else if named {
+                return Err(self.error(start, ParseErrorKind::PositionalAfterNamed));
+            } else {
+                index += 1;
+                params
+                    .get(index - 1)
+                    .ok_or(self.error(start, ParseErrorKind::TooManyArgs))?
+            };
+
+            self.skip_whitespace();
+            let value_start = self.pos;
+            let value = self.number()?;
+            let value = match (pass, self.eat(b'%')) {
+                (Pass::Values, false) => Some(value),
+                (Pass::Percents(range), true) => Some(percent(param, range, value)),
+                _ => None,
+            };
+            if let Some(value) = value {
+                effect
+                    .set_param(param.name, value)
+                    .map_err(|error| self.error(value_start, ParseErrorKind::Rejected(error)))?;
+            }
+
+            if !self.eat(b',') {
+                return self.expect(b')');
+            }
+        }
+    }
+}
+
+impl<S: Sample, const N: usize> Registry<'_, S, N> {
+    /// Parses chain in [crate::dsl] form and appends its effects to `pipeline`
+    pub fn parse<const CAP: usize>(
+        &self,
+        text: &str,
+        pipeline: &mut Pipeline<S, CAP, N>,
+    ) -> Result<(), ParseError> {
+        let mut parser = Parser { text, pos: 0 };
+        parser.skip_whitespace();
+        if parser.peek().is_none() {
+            return Ok(());
+        }
+
+        loop {
+            parser.skip_whitespace();
+            let start = parser.pos;
+            let effect = parser.effect(self)?;
+            pipeline
+                .push(effect)
+                .map_err(|error| parser.error(start, ParseErrorKind::Rejected(error)))?;
+
+            parser.skip_whitespace();
+            if parser.peek().is_none() {
+                return Ok(());
+            }
+            parser.expect(b'|')?;
+        }
+    }
+
+    /// Formats `effects` in [crate::dsl] form. Parameters that match the defaults are omitted.
+    /// Fails with [RegistryError::Unregistered] on effects that are not registered and with
+    /// [RegistryError::Rejected] if parameters can't be written back
+    pub fn display<'e, I>(&self, effects: I) -> Result<ChainDisplay<'_, S, N, I>, RegistryError>
+    where
+        I: IntoIterator<Item = &'e DynEffect<S, N>> + Clone,
+    {
+        let display = ChainDisplay {
+            registry: *self,
+            effects,
+        };
+        match display.write(&mut Discard) {
+            Err(WriteError::Registry(error)) => Err(error),
+            _ => Ok(display),
+        }
+    }
+}
+
+/// Text form of effect chain, see [Registry::display]
+pub struct ChainDisplay<'a, S: Sample, const N: usize, I> {
+    registry: Registry<'a, S, N>,
+    effects: I,
+}
+
+enum WriteError {
+    Fmt(fmt::Error),
+    Registry(RegistryError),
+}
+
+impl From<fmt::Error> for WriteError {
+    fn from(error: fmt::Error) -> Self {
+        WriteError::Fmt(error)
+    }
+}
+
+/// [fmt::Write] that drops the text, used to check the chain before it is displayed
+struct Discard;
+
+impl fmt::Write for Discard {
+    fn write_str(&mut self, _s: &str) -> fmt::Result {
+        Ok(())
+    }
+}
+
+impl<'a, 'e, S: Sample, const N: usize, I> ChainDisplay<'a, S, N, I>
+where
+    I: IntoIterator<Item = &'e DynEffect<S, N>> + Clone,
+{
+    fn write(&self, f: &mut impl fmt::Write) -> Result<(), WriteError> {
+        for (i, effect) in self.effects.clone().into_iter().enumerate() {
+            if i > 0 {
+                write!(f, " | ")?;
+            }
+            if !effect.is_enabled() {
+                write!(f, "!")?;
+            }
+
+            let descriptor = effect
+                .descriptor()
+                .ok_or(WriteError::Registry(RegistryError::Unregistered))?;
+            let entry = self
+                .registry
+                .by_name(descriptor.name)
+                .ok_or(WriteError::Registry(RegistryError::Unregistered))?;
+            write!(f, "{}(", descriptor.name)?;
+
+            // Parameters are written only if they differ from the effect built from
+            // already written ones, so derived parameters are skipped too
+            let mut written = (entry.create)();
+            let mut positional = true;
+            let mut first = true;
+            for param in descriptor.params {
+                let value = effect.param(param.name).unwrap_or(param.default);
+                if written.param(param.name) == Some(value) {
+                    positional = false;
+                    continue;
+                }
+
+                if !first {
+                    write!(f, ", ")?;
+                }
+                if !positional {
+                    write!(f, "{}=", param.name)?;
+                }
+                write!(f, "{value}")?;
+                written
+                    .set_param(param.name, value)
+                    .map_err(|error| WriteError::Registry(RegistryError::Rejected(error)))?;
+                first = false;
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, 'e, S: Sample, const N: usize, I> fmt::Display for ChainDisplay<'a, S, N, I>
+where
+    I: IntoIterator<Item = &'e DynEffect<S, N>> + Clone,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Registry errors are ruled out by Registry::display
+        match self.write(f) {
+            Err(WriteError::Fmt(error)) => Err(error),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Axis,
+        effects::{Curve, Deadzone, Expo, Lerp},
+    };
+    use core::fmt::Write;
+
+    /// Fixed-size [fmt::Write] buffer
+    struct Text {
+        data: [u8; 256],
+        len: usize,
+    }
+
+    impl Text {
+        fn new() -> Self {
+            Self {
+                data: [0; 256],
+                len: 0,
+            }
+        }
+
+        fn as_str(&self) -> &str {
+            core::str::from_utf8(&self.data[..self.len]).unwrap()
+        }
+    }
+
+    impl Write for Text {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len + s.len();
+            self.data
+                .get_mut(self.len..end)
+                .ok_or(fmt::Error)?
+                .copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn parse_and_display() {
+        let registry: Registry = Registry::new();
+        let mut pipeline: Pipeline = Pipeline::new();
+        registry
+            .parse(
+                " deadzone(3%) | expo( 0.3 , center = 30000 ) |!lerp(0.25)|smooth",
+                &mut pipeline,
+            )
+            .unwrap();
+        assert_eq!(pipeline.len(), 4);
+        assert_eq!(pipeline.get(0).unwrap().param("inner"), Some(1966.0));
+        assert_eq!(pipeline.get(1).unwrap().param("high"), Some(0.3));
+        assert!(!pipeline.get(2).unwrap().is_enabled());
+
+        let mut text = Text::new();
+        write!(text, "{}", registry.display(&pipeline).unwrap()).unwrap();
+        assert_eq!(
+            text.as_str(),
+            "deadzone(1966) | expo(0.3, center=30000) | !lerp(0.25) | smooth()"
+        );
+
+        let mut reparsed: Pipeline = Pipeline::new();
+        registry.parse(text.as_str(), &mut reparsed).unwrap();
+        let mut again = Text::new();
+        write!(again, "{}", registry.display(&reparsed).unwrap()).unwrap();
+        assert_eq!(again.as_str(), text.as_str());
+    }
+
+    #[test]
+    fn percent_of_effect_range() {
+        let registry: Registry = Registry::new();
+        let mut pipeline: Pipeline = Pipeline::new();
+        registry
+            .parse(
+                "deadzone(inner=3%, center=50%, min=100, max=1100) | expo(75%) | smooth(50%)",
+                &mut pipeline,
+            )
+            .unwrap();
+
+        let deadzone = pipeline.get_mut(0).unwrap();
+        assert_eq!(deadzone.param("inner"), Some(30.0));
+        assert_eq!(deadzone.param("center"), Some(600.0));
+        assert_eq!(deadzone.update(620), 600);
+        assert_eq!(deadzone.update(1100), 1100);
+        assert_eq!(deadzone.update(700), 674);
+        // Without `min`/`max` parameters `%` is relative to the descriptor range
+        assert_eq!(pipeline.get(1).unwrap().param("factor"), Some(0.5));
+        assert_eq!(pipeline.get(2).unwrap().param("speed"), Some(32767.0));
+    }
+
+    #[test]
+    fn display_unregistered() {
+        static CURVE: Curve<2> = Curve::new([(0, 0), (1000, 1000)]);
+
+        let registry: Registry = Registry::new();
+        let chain: [DynEffect; 2] = [Lerp::new(0.5).into(), (&CURVE).into()];
+        assert_eq!(
+            registry.display(&chain).err(),
+            Some(RegistryError::Unregistered)
+        );
+        assert!(registry.display(&chain[..1]).is_ok());
+    }
+
+    #[test]
+    fn parsed_chain_matches_effects() {
+        let registry: Registry = Registry::new();
+        let mut pipeline: Pipeline = Pipeline::new();
+        registry
+            .parse(
+                "deadzone(20, 0, 500, 0, 1000) | expo(low=0.2, high=-0.4, min=0, max=1000, center=500) | lerp(0.5)",
+                &mut pipeline,
+            )
+            .unwrap();
+        let mut effects: [DynEffect; 3] = [
+            Deadzone::new(0, 1000, 20, 0).into(),
+            Expo::asymmetric(0, 1000, 0.2, -0.4).into(),
+            Lerp::new(0.5).into(),
+        ];
+
+        let mut parsed = Axis::new(0, 1000, false);
+        let mut original = Axis::new(0, 1000, false);
+        for value in (0..=1000).step_by(25) {
+            parsed.update(value, &mut pipeline);
+            original.update(value, effects.iter_mut());
+            assert_eq!(parsed.output(0, 1000), original.output(0, 1000));
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        let registry: Registry = Registry::new();
+        let error = |text: &str| {
+            let mut pipeline: Pipeline<u16, 2> = Pipeline::new();
+            let error = registry.parse(text, &mut pipeline).unwrap_err();
+            (error.position, error.kind)
+        };
+
+        assert_eq!(
+            error("lerp(0.5) | blur(2)"),
+            (12, ParseErrorKind::UnknownEffect)
+        );
+        assert_eq!(error("lerp(x)"), (6, ParseErrorKind::Expected('=')));
+        assert_eq!(error("lerp(0.5"), (8, ParseErrorKind::Expected(')')));
+        assert_eq!(error("lerp(0.5, 1)"), (10, ParseErrorKind::TooManyArgs));
+        assert_eq!(error("lerp(gain=1)"), (5, ParseErrorKind::UnknownParam));
+        assert_eq!(error("lerp(1..2)"), (5, ParseErrorKind::InvalidNumber));
+        assert_eq!(error("lerp lerp"), (5, ParseErrorKind::Expected('|')));
+        assert_eq!(error("lerp |"), (6, ParseErrorKind::ExpectedName));
+        assert_eq!(
+            error("expo(high=0.5, 0.5)"),
+            (15, ParseErrorKind::PositionalAfterNamed)
+        );
+        assert_eq!(
+            error("lerp(2)"),
+            (5, ParseErrorKind::Rejected(AxisError::InvalidFactor))
+        );
+        assert_eq!(
+            error("lerp | lerp | lerp"),
+            (14, ParseErrorKind::Rejected(AxisError::PipelineFull))
+        );
+
+        let mut pipeline: Pipeline = Pipeline::new();
+        assert!(registry.parse("  ", &mut pipeline).is_ok());
+        assert!(pipeline.is_empty());
+    }
+}
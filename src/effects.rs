@@ -1,21 +1,37 @@
 #![allow(unused_imports)]
 
-use crate::{DynEffect, Effect};
+use crate::{
+    AxisError, DynEffect, Effect, EffectDescriptor, ParamDescriptor, ParamUnit, Sample,
+    error::{check_factor, check_range, check_sample},
+};
 use micromath::F32Ext;
 
+/// Implements `From<$type> for DynEffect<S, M>`, extra generics of `$type` go in brackets:
+/// `impl_into_dyn_effect!([const N: usize,] Curve<N, S>)`
 macro_rules! impl_into_dyn_effect {
-    ($type:ty) => {
-        impl Into<DynEffect> for $type {
-            fn into(self) -> DynEffect {
-                DynEffect::new(self)
+    ([$($generics:tt)*] $type:ty) => {
+        impl<$($generics)* S: Sample, const M: usize> From<$type> for DynEffect<S, M> {
+            fn from(effect: $type) -> DynEffect<S, M> {
+                DynEffect::new(effect)
             }
         }
     };
+    ($type:ty) => {
+        impl_into_dyn_effect!([] $type);
+    };
 }
 
+pub(crate) use impl_into_dyn_effect;
+
 /// Linear interpolation filter
-#[derive(Default)]
+#[derive(Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "crate::serialize::LerpData")
+)]
 pub struct Lerp {
+    #[cfg_attr(feature = "serde", serde(skip))]
     smoothed_value: Option<f32>,
     lerp_factor: f32,
 }
@@ -27,43 +43,984 @@ impl Lerp {
             lerp_factor: factor,
         }
     }
+
+    pub const DESCRIPTOR: EffectDescriptor = EffectDescriptor {
+        name: "lerp",
+        params: &[LERP_FACTOR],
+    };
+
+    /// Same as [Lerp::new], but rejects factors outside of `0.0..=1.0`
+    pub fn try_new(factor: f32) -> Result<Self, AxisError> {
+        check_factor(factor, 0.0, 1.0)?;
+        Ok(Self::new(factor))
+    }
 }
 
-impl Effect for Lerp {
-    fn update(&mut self, value: u16) -> u16 {
-        let value_f32 = value as f32;
+impl<S: Sample> Effect<S> for Lerp {
+    fn update(&mut self, value: S) -> S {
+        let value_f32 = value.to_f32();
         let sv = self.smoothed_value.unwrap_or(value_f32);
         let new_value = sv + (value_f32 - sv) * self.lerp_factor;
 
         self.smoothed_value = Some(new_value);
-        new_value.floor() as u16
+        S::from_f32(new_value.floor())
+    }
+
+    fn reset(&mut self) {
+        self.smoothed_value = None;
+    }
+
+    fn param(&self, name: &str) -> Option<f32> {
+        match name {
+            "factor" => Some(self.lerp_factor),
+            _ => None,
+        }
+    }
+
+    fn set_param(&mut self, name: &str, value: f32) -> Result<(), AxisError> {
+        match name {
+            "factor" => {
+                check_factor(value, 0.0, 1.0)?;
+                self.lerp_factor = value;
+                Ok(())
+            }
+            _ => Err(AxisError::UnknownParam),
+        }
+    }
+
+    fn descriptor(&self) -> Option<&'static EffectDescriptor> {
+        Some(const { &Self::DESCRIPTOR })
     }
 }
 
+/// `factor` parameter of [Lerp] and [crate::fixed::Lerp]
+pub(crate) const LERP_FACTOR: ParamDescriptor =
+    ParamDescriptor::new("factor", 0.0, 1.0, 0.5, ParamUnit::Factor);
+
+impl_into_dyn_effect!(Lerp);
+
 /// Laggy-smooth effect
-#[derive(Default)]
-pub struct Smooth {
-    current: u16,
-    target: u16,
-    speed: u16
+#[derive(Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "crate::serialize::SmoothData<S>")
+)]
+pub struct Smooth<S: Sample = u16> {
+    #[cfg_attr(feature = "serde", serde(skip))]
+    current: S,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    target: S,
+    speed: S,
 }
 
-impl Smooth {
-    pub fn new(speed: u16) -> Self {
+impl<S: Sample> Smooth<S> {
+    pub fn new(speed: S) -> Self {
         Self {
-            current: 0,
-            target: 0,
-            speed
+            current: S::default(),
+            target: S::default(),
+            speed,
         }
     }
+
+    pub const DESCRIPTOR: EffectDescriptor = EffectDescriptor {
+        name: "smooth",
+        params: &[ParamDescriptor::new(
+            "speed",
+            1.0,
+            S::MAX_F32,
+            1.0,
+            ParamUnit::Distance,
+        )],
+    };
+
+    /// Same as [Smooth::new], but rejects non-positive speed
+    pub fn try_new(speed: S) -> Result<Self, AxisError> {
+        if speed <= S::default() {
+            return Err(AxisError::InvalidFactor);
+        }
+        Ok(Self::new(speed))
+    }
 }
 
-impl Effect for Smooth {
-    fn update(&mut self, input: u16) -> u16 {
+impl<S: Sample> Effect<S> for Smooth<S> {
+    fn update(&mut self, input: S) -> S {
         self.target = input;
-        self.current = self.current.saturating_add(self.speed);
-        self.current.clamp(u16::MIN, self.target)
+        self.current = S::from_i64(self.current.to_i64() + self.speed.to_i64());
+        self.current.clamp(S::MIN, self.target)
+    }
+
+    fn reset(&mut self) {
+        self.current = S::default();
+        self.target = S::default();
+    }
+
+    fn param(&self, name: &str) -> Option<f32> {
+        self.param_sample(name).map(S::to_f32)
+    }
+
+    fn set_param(&mut self, name: &str, value: f32) -> Result<(), AxisError> {
+        set_sample_param(self, name, value)
+    }
+
+    fn param_sample(&self, name: &str) -> Option<S> {
+        match name {
+            "speed" => Some(self.speed),
+            _ => None,
+        }
+    }
+
+    fn set_param_sample(&mut self, name: &str, value: S) -> Result<(), AxisError> {
+        match name {
+            "speed" => {
+                self.speed = Self::try_new(value)?.speed;
+                Ok(())
+            }
+            _ => Err(AxisError::UnknownParam),
+        }
+    }
+
+    fn descriptor(&self) -> Option<&'static EffectDescriptor> {
+        Some(const { &Self::DESCRIPTOR })
+    }
+}
+
+impl_into_dyn_effect!(Smooth<S>);
+
+/// Deadzone effect. Snaps values near `center` to the center (inner zone), saturates values
+/// near `min`/`max` (outer zone) and rescales the remaining travel to the full `min..=max` range.
+#[derive(Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "crate::serialize::DeadzoneData<S>")
+)]
+pub struct Deadzone<S: Sample = u16> {
+    min: S,
+    max: S,
+    center: S,
+    inner: S,
+    outer: S,
+}
+
+/// Midpoint of `min..=max`
+pub(crate) fn midpoint<S: Sample>(min: S, max: S) -> S {
+    S::from_i64(min.to_i64() + (max.to_i64() - min.to_i64()) / 2)
+}
+
+impl<S: Sample> Deadzone<S> {
+    /// Creates deadzone for `min..=max` range centered at midpoint.
+    /// `inner` is the radius of the center zone, `outer` is width of saturation zone at each end.
+    pub fn new(min: S, max: S, inner: S, outer: S) -> Self {
+        Self {
+            min,
+            max,
+            center: midpoint(min, max),
+            inner,
+            outer,
+        }
+    }
+
+    pub const DESCRIPTOR: EffectDescriptor = EffectDescriptor {
+        name: "deadzone",
+        params: &[
+            ParamDescriptor::distance::<S>("inner", 0.0),
+            ParamDescriptor::distance::<S>("outer", 0.0),
+            ParamDescriptor::midpoint::<S>("center"),
+            ParamDescriptor::sample::<S>("min", S::MIN_F32),
+            ParamDescriptor::sample::<S>("max", S::MAX_F32),
+        ],
+    };
+
+    /// Same as [Deadzone::new], but validates the range and zone widths
+    pub fn try_new(min: S, max: S, inner: S, outer: S) -> Result<Self, AxisError> {
+        check_range(min, max)?;
+        if inner < S::default() || outer < S::default() {
+            return Err(AxisError::InvalidFactor);
+        }
+        Ok(Self::new(min, max, inner, outer))
+    }
+
+    /// Moves the center zone to `center`
+    pub fn with_center(mut self, center: S) -> Self {
+        self.center = center.clamp(self.min, self.max);
+        self
+    }
+}
+
+/// Reads `min`, `max` or `center` parameter of ranged effect
+pub(crate) fn range_param<S: Sample>(name: &str, min: S, max: S, center: S) -> Option<S> {
+    match name {
+        "min" => Some(min),
+        "max" => Some(max),
+        "center" => Some(center),
+        _ => None,
+    }
+}
+
+/// Sets `min`, `max` or `center` parameter of ranged effect, keeping center inside of the range
+pub(crate) fn set_range_param<S: Sample>(
+    name: &str,
+    value: S,
+    min: &mut S,
+    max: &mut S,
+    center: &mut S,
+) -> Result<(), AxisError> {
+    match name {
+        "min" => {
+            check_range(value, *max)?;
+            *min = value;
+        }
+        "max" => {
+            check_range(*min, value)?;
+            *max = value;
+        }
+        "center" => *center = value,
+        _ => return Err(AxisError::UnknownParam),
+    }
+    *center = (*center).clamp(*min, *max);
+    Ok(())
+}
+
+/// Sets `f32` parameter `name` read with [Effect::param_sample] through
+/// [Effect::set_param_sample], rejecting values outside of the sample range
+pub(crate) fn set_sample_param<S: Sample>(
+    effect: &mut impl Effect<S>,
+    name: &str,
+    value: f32,
+) -> Result<(), AxisError> {
+    if effect.param_sample(name).is_none() {
+        return Err(AxisError::UnknownParam);
+    }
+    effect.set_param_sample(name, check_sample(value)?)
+}
+
+/// `a * b / divisor` rounded toward zero for distances between `S` values. Products of
+/// full-range `u32`/`i32` distances overflow `i64`, so `i128` is only compiled in for them
+fn mul_div<S: Sample>(a: i64, b: i64, divisor: i64) -> i64 {
+    if size_of::<S>() <= 2 {
+        a * b / divisor
+    } else {
+        (a as i128 * b as i128 / divisor as i128) as i64
+    }
+}
+
+/// Maps `value` from `from_min..=from_max` to `to_min..=to_max`
+fn rescale<S: Sample>(value: i64, from_min: i64, from_max: i64, to_min: i64, to_max: i64) -> i64 {
+    if from_max <= from_min {
+        return to_min;
+    }
+
+    to_min + mul_div::<S>(value - from_min, to_max - to_min, from_max - from_min)
+}
+
+impl<S: Sample> Effect<S> for Deadzone<S> {
+    fn update(&mut self, input: S) -> S {
+        let value = input.clamp(self.min, self.max).to_i64();
+        let (min, max, center) = (self.min.to_i64(), self.max.to_i64(), self.center.to_i64());
+        let (inner, outer) = (self.inner.to_i64(), self.outer.to_i64());
+
+        let low = (min + outer).min(center);
+        let high = (max - outer).max(center);
+        let inner_low = (center - inner).max(low);
+        let inner_high = (center + inner).min(high);
+
+        let output = if value <= low && low < center {
+            min
+        } else if value >= high && high > center {
+            max
+        } else if value < inner_low {
+            rescale::<S>(value, low, inner_low, min, center)
+        } else if value > inner_high {
+            rescale::<S>(value, inner_high, high, center, max)
+        } else {
+            center
+        };
+
+        S::from_i64(output)
+    }
+
+    fn param(&self, name: &str) -> Option<f32> {
+        self.param_sample(name).map(S::to_f32)
+    }
+
+    fn set_param(&mut self, name: &str, value: f32) -> Result<(), AxisError> {
+        set_sample_param(self, name, value)
+    }
+
+    fn param_sample(&self, name: &str) -> Option<S> {
+        match name {
+            "inner" => Some(self.inner),
+            "outer" => Some(self.outer),
+            _ => range_param(name, self.min, self.max, self.center),
+        }
+    }
+
+    fn set_param_sample(&mut self, name: &str, value: S) -> Result<(), AxisError> {
+        let zone = match name {
+            "inner" => &mut self.inner,
+            "outer" => &mut self.outer,
+            _ => {
+                return set_range_param(
+                    name,
+                    value,
+                    &mut self.min,
+                    &mut self.max,
+                    &mut self.center,
+                );
+            }
+        };
+
+        if value < S::default() {
+            return Err(AxisError::InvalidFactor);
+        }
+        *zone = value;
+        Ok(())
+    }
+
+    fn descriptor(&self) -> Option<&'static EffectDescriptor> {
+        Some(const { &Self::DESCRIPTOR })
+    }
+}
+
+impl_into_dyn_effect!(Deadzone<S>);
+
+/// RC-style expo response curve around `center`.
+/// Positive factor softens response near the center, negative factor ("reverse expo") sharpens it.
+#[derive(Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "crate::serialize::ExpoData<S>")
+)]
+pub struct Expo<S: Sample = u16> {
+    min: S,
+    max: S,
+    center: S,
+    low: f32,
+    high: f32,
+}
+
+impl<S: Sample> Expo<S> {
+    /// Creates symmetric expo for `min..=max` range centered at midpoint, `factor` is in `-1.0..=1.0`
+    pub fn new(min: S, max: S, factor: f32) -> Self {
+        Self::asymmetric(min, max, factor, factor)
+    }
+
+    /// Creates expo with separate factors for `min..center` (`low`) and `center..max` (`high`) sides
+    pub fn asymmetric(min: S, max: S, low: f32, high: f32) -> Self {
+        Self {
+            min,
+            max,
+            center: midpoint(min, max),
+            low: low.clamp(-1.0, 1.0),
+            high: high.clamp(-1.0, 1.0),
+        }
+    }
+
+    pub const DESCRIPTOR: EffectDescriptor = EffectDescriptor {
+        name: "expo",
+        params: &expo_params::<S>(),
+    };
+
+    /// Same as [Expo::new], but validates the range and rejects factor outside of `-1.0..=1.0`
+    pub fn try_new(min: S, max: S, factor: f32) -> Result<Self, AxisError> {
+        Self::try_asymmetric(min, max, factor, factor)
+    }
+
+    /// Same as [Expo::asymmetric], but validates the range and factors
+    pub fn try_asymmetric(min: S, max: S, low: f32, high: f32) -> Result<Self, AxisError> {
+        check_range(min, max)?;
+        check_factor(low, -1.0, 1.0)?;
+        check_factor(high, -1.0, 1.0)?;
+        Ok(Self::asymmetric(min, max, low, high))
+    }
+
+    /// Moves the curve center to `center`
+    pub fn with_center(mut self, center: S) -> Self {
+        self.center = center.clamp(self.min, self.max);
+        self
+    }
+}
+
+/// Parameters of [Expo] and [crate::fixed::Expo]
+pub(crate) const fn expo_params<S: Sample>() -> [ParamDescriptor; 6] {
+    [
+        ParamDescriptor::new("factor", -1.0, 1.0, 0.0, ParamUnit::Factor),
+        ParamDescriptor::new("low", -1.0, 1.0, 0.0, ParamUnit::Factor),
+        ParamDescriptor::new("high", -1.0, 1.0, 0.0, ParamUnit::Factor),
+        ParamDescriptor::midpoint::<S>("center"),
+        ParamDescriptor::sample::<S>("min", S::MIN_F32),
+        ParamDescriptor::sample::<S>("max", S::MAX_F32),
+    ]
+}
+
+/// Expo curve for normalized `0.0..=1.0` deflection
+pub(crate) fn expo_curve(x: f32, factor: f32) -> f32 {
+    if factor >= 0.0 {
+        factor * x * x * x + (1.0 - factor) * x
+    } else {
+        let t = 1.0 - x;
+        1.0 - (-factor * t * t * t + (1.0 + factor) * t)
+    }
+}
+
+impl<S: Sample> Effect<S> for Expo<S> {
+    fn update(&mut self, input: S) -> S {
+        let value = input.clamp(self.min, self.max).to_i64();
+        let (min, max, center) = (self.min.to_i64(), self.max.to_i64(), self.center.to_i64());
+
+        let output = if value < center {
+            let span = (center - min) as f32;
+            let x = (center - value) as f32 / span;
+            center as f32 - expo_curve(x, self.low) * span
+        } else if value > center {
+            let span = (max - center) as f32;
+            let x = (value - center) as f32 / span;
+            center as f32 + expo_curve(x, self.high) * span
+        } else {
+            return self.center;
+        };
+
+        S::from_f32(output.round())
+    }
+
+    fn param(&self, name: &str) -> Option<f32> {
+        match name {
+            "factor" => Some((self.low + self.high) / 2.0),
+            "low" => Some(self.low),
+            "high" => Some(self.high),
+            _ => self.param_sample(name).map(S::to_f32),
+        }
+    }
+
+    fn set_param(&mut self, name: &str, value: f32) -> Result<(), AxisError> {
+        let (low, high) = match name {
+            "factor" => (value, value),
+            "low" => (value, self.high),
+            "high" => (self.low, value),
+            _ => return set_sample_param(self, name, value),
+        };
+
+        check_factor(value, -1.0, 1.0)?;
+        (self.low, self.high) = (low, high);
+        Ok(())
+    }
+
+    fn param_sample(&self, name: &str) -> Option<S> {
+        range_param(name, self.min, self.max, self.center)
+    }
+
+    fn set_param_sample(&mut self, name: &str, value: S) -> Result<(), AxisError> {
+        set_range_param(name, value, &mut self.min, &mut self.max, &mut self.center)
+    }
+
+    fn descriptor(&self) -> Option<&'static EffectDescriptor> {
+        Some(const { &Self::DESCRIPTOR })
+    }
+}
+
+impl_into_dyn_effect!(Expo<S>);
+
+/// Piecewise-linear response curve through `N` `(input, output)` points.
+/// Points must be sorted by input. Curves larger than [DynEffect] storage can be used in
+/// the chain by reference to a static table:
+///
+/// ```
+/// use axis::{DynEffect, effects::Curve};
+///
+/// static PEDAL: Curve<5> = Curve::new([(0, 0), (100, 10), (200, 40), (300, 120), (400, 400)]);
+/// let effect: DynEffect = (&PEDAL).into();
+/// ```
+#[derive(Clone, Copy)]
+pub struct Curve<const N: usize, S: Sample = u16> {
+    points: [(S, S); N],
+}
+
+impl<const N: usize, S: Sample> Curve<N, S> {
+    pub const fn new(points: [(S, S); N]) -> Self {
+        Self { points }
+    }
+
+    pub fn points(&self) -> &[(S, S); N] {
+        &self.points
+    }
+
+    /// Evaluates the curve at `input`, values outside of the curve are clamped to the end points
+    pub fn evaluate(&self, input: S) -> S {
+        let i = match find_segment(&self.points, input) {
+            Ok(i) => i,
+            Err(output) => return output,
+        };
+
+        let (x0, y0) = (self.points[i].0.to_i64(), self.points[i].1.to_i64());
+        let (x1, y1) = (self.points[i + 1].0.to_i64(), self.points[i + 1].1.to_i64());
+        S::from_i64(y0 + mul_div::<S>(y1 - y0, input.to_i64() - x0, x1 - x0))
+    }
+}
+
+const CURVE_DESCRIPTOR: EffectDescriptor = EffectDescriptor {
+    name: "curve",
+    params: &[],
+};
+
+/// Finds index of the segment `points[i]..points[i + 1]` containing `input`.
+/// Returns `Err` with output value if `input` is outside of the points or hits zero-width segment
+pub(crate) fn find_segment<S: Sample>(points: &[(S, S)], input: S) -> Result<usize, S> {
+    let Some(&(first_x, first_y)) = points.first() else {
+        return Err(input);
+    };
+
+    if input <= first_x {
+        return Err(first_y);
+    }
+
+    for (i, pair) in points.windows(2).enumerate() {
+        let (x0, _) = pair[0];
+        let (x1, y1) = pair[1];
+        if input <= x1 {
+            return if x1 == x0 { Err(y1) } else { Ok(i) };
+        }
+    }
+
+    Err(points[points.len() - 1].1)
+}
+
+impl<const N: usize, S: Sample> Effect<S> for Curve<N, S> {
+    fn update(&mut self, input: S) -> S {
+        self.evaluate(input)
+    }
+
+    fn descriptor(&self) -> Option<&'static EffectDescriptor> {
+        Some(&CURVE_DESCRIPTOR)
+    }
+}
+
+impl<const N: usize, S: Sample> Effect<S> for &'static Curve<N, S> {
+    fn update(&mut self, input: S) -> S {
+        self.evaluate(input)
+    }
+
+    fn descriptor(&self) -> Option<&'static EffectDescriptor> {
+        Some(&CURVE_DESCRIPTOR)
+    }
+}
+
+impl_into_dyn_effect!([const N: usize,] Curve<N, S>);
+
+impl_into_dyn_effect!([const N: usize,] &'static Curve<N, S>);
+
+/// Monotone cubic spline through `N` `(input, output)` points. Points must be sorted by input.
+///
+/// Tangents are chosen with Fritsch-Butland (harmonic mean) method, so the curve never
+/// overshoots the points and never reverses direction between them. Like [Curve], large splines
+/// can be used in the chain by reference to a static table.
+#[derive(Clone, Copy)]
+pub struct Spline<const N: usize, S: Sample = u16> {
+    points: [(S, S); N],
+    tangents: [f32; N],
+}
+
+const SPLINE_DESCRIPTOR: EffectDescriptor = EffectDescriptor {
+    name: "spline",
+    params: &[],
+};
+
+/// Slope of the segment `points[i]..points[i + 1]`
+const fn secant(points: &[(f32, f32)], i: usize) -> f32 {
+    let dx = points[i + 1].0 - points[i].0;
+    if dx == 0.0 {
+        return 0.0;
+    }
+    (points[i + 1].1 - points[i].1) / dx
+}
+
+const fn spline_tangent(points: &[(f32, f32)], i: usize) -> f32 {
+    let n = points.len();
+    if n < 2 {
+        return 0.0;
+    }
+    if i == 0 {
+        return secant(points, 0);
+    }
+    if i == n - 1 {
+        return secant(points, n - 2);
+    }
+
+    let d0 = secant(points, i - 1);
+    let d1 = secant(points, i);
+    if d0 * d1 <= 0.0 {
+        return 0.0;
+    }
+
+    let h0 = points[i].0 - points[i - 1].0;
+    let h1 = points[i + 1].0 - points[i].0;
+    3.0 * (h0 + h1) / ((2.0 * h1 + h0) / d0 + (h1 + 2.0 * h0) / d1)
+}
+
+const fn spline_tangents<const N: usize>(points: &[(f32, f32); N]) -> [f32; N] {
+    let mut tangents = [0.0; N];
+    let mut i = 0;
+    while i < N {
+        tangents[i] = spline_tangent(points, i);
+        i += 1;
+    }
+    tangents
+}
+
+impl<const N: usize> Spline<N> {
+    /// Creates `u16` spline, usable in `static` tables
+    pub const fn new(points: [(u16, u16); N]) -> Self {
+        let mut points_f32 = [(0.0, 0.0); N];
+        let mut i = 0;
+        while i < N {
+            points_f32[i] = (points[i].0 as f32, points[i].1 as f32);
+            i += 1;
+        }
+
+        Self {
+            points,
+            tangents: spline_tangents(&points_f32),
+        }
+    }
+}
+
+impl<const N: usize, S: Sample> Spline<N, S> {
+    pub fn from_points(points: [(S, S); N]) -> Self {
+        let points_f32 = points.map(|(x, y)| (x.to_f32(), y.to_f32()));
+        Self {
+            points,
+            tangents: spline_tangents(&points_f32),
+        }
+    }
+
+    pub fn points(&self) -> &[(S, S); N] {
+        &self.points
+    }
+
+    /// Evaluates the spline at `input`, values outside of the spline are clamped to the end points
+    pub fn evaluate(&self, input: S) -> S {
+        let i = match find_segment(&self.points, input) {
+            Ok(i) => i,
+            Err(output) => return output,
+        };
+
+        let (x0, y0) = self.points[i];
+        let (x1, y1) = self.points[i + 1];
+        let h = (x1.to_i64() - x0.to_i64()) as f32;
+        let t = (input.to_i64() - x0.to_i64()) as f32 / h;
+        let t2 = t * t;
+        let t3 = t2 * t;
+
+        let y = (2.0 * t3 - 3.0 * t2 + 1.0) * y0.to_f32()
+            + (t3 - 2.0 * t2 + t) * h * self.tangents[i]
+            + (3.0 * t2 - 2.0 * t3) * y1.to_f32()
+            + (t3 - t2) * h * self.tangents[i + 1];
+
+        S::from_f32(y.round()).clamp(y0.min(y1), y0.max(y1))
+    }
+}
+
+impl<const N: usize, S: Sample> Effect<S> for Spline<N, S> {
+    fn update(&mut self, input: S) -> S {
+        self.evaluate(input)
+    }
+
+    fn descriptor(&self) -> Option<&'static EffectDescriptor> {
+        Some(&SPLINE_DESCRIPTOR)
+    }
+}
+
+impl<const N: usize, S: Sample> Effect<S> for &'static Spline<N, S> {
+    fn update(&mut self, input: S) -> S {
+        self.evaluate(input)
+    }
+
+    fn descriptor(&self) -> Option<&'static EffectDescriptor> {
+        Some(&SPLINE_DESCRIPTOR)
+    }
+}
+
+impl_into_dyn_effect!([const N: usize,] Spline<N, S>);
+
+impl_into_dyn_effect!([const N: usize,] &'static Spline<N, S>);
+
+/// Moving average (boxcar) filter over the last `N` samples. Keeps a running sum, so each sample
+/// costs O(1) regardless of `N`, which is at most 255. Until the window is filled, averages the
+/// samples seen so far. The sum is kept in [Sample::Sum], so windows of up to 5 `u16` samples
+/// fit in the default [DynEffect] storage, larger ones can be used with a larger storage size, see [crate::Axis::with_effect_size],
+/// or in a [crate::Chain].
+#[derive(Clone, Copy)]
+pub struct MovingAverage<const N: usize, S: Sample = u16> {
+    samples: [S; N],
+    sum: S::Sum,
+    /// Index of the oldest sample, replaced by the next one
+    index: u8,
+    len: u8,
+}
+
+impl<const N: usize, S: Sample> MovingAverage<N, S> {
+    pub fn new() -> Self {
+        const {
+            assert!(N > 0, "MovingAverage window must not be empty");
+            assert!(
+                N <= u8::MAX as usize,
+                "MovingAverage window must be at most 255"
+            );
+        }
+        Self {
+            samples: [S::default(); N],
+            sum: S::Sum::default(),
+            index: 0,
+            len: 0,
+        }
+    }
+}
+
+impl<const N: usize, S: Sample> Default for MovingAverage<N, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+const MOVING_AVERAGE_DESCRIPTOR: EffectDescriptor = EffectDescriptor {
+    name: "moving_average",
+    params: &[],
+};
+
+impl<const N: usize, S: Sample> Effect<S> for MovingAverage<N, S> {
+    fn update(&mut self, input: S) -> S {
+        let index = self.index as usize;
+        if self.len as usize == N {
+            self.sum = self.sum - self.samples[index].into();
+        } else {
+            self.len += 1;
+        }
+        self.samples[index] = input;
+        self.sum = self.sum + input.into();
+        self.index = ((index + 1) % N) as u8;
+
+        S::from_i64(self.sum.into().div_euclid(self.len as i64))
+    }
+
+    fn reset(&mut self) {
+        self.sum = S::Sum::default();
+        self.index = 0;
+        self.len = 0;
+    }
+
+    fn descriptor(&self) -> Option<&'static EffectDescriptor> {
+        Some(&MOVING_AVERAGE_DESCRIPTOR)
+    }
+}
+
+impl_into_dyn_effect!([const N: usize,] MovingAverage<N, S>);
+
+/// Median filter over the last `N` samples, `N` must be odd. Rejects single-sample spikes while
+/// keeping sharp edges, which [Lerp] would smear. Stores only the samples and sorts a copy of
+/// them on each update, which costs O(N²), so it is meant for small windows such as 3, 5 or 7
+/// (`Median<7, u16>` fits the default [DynEffect] storage). Until the window is filled, returns
+/// the lower median of the samples seen so far.
+#[derive(Clone, Copy)]
+pub struct Median<const N: usize, S: Sample = u16> {
+    /// Ring buffer of the last `len` samples
+    samples: [S; N],
+    /// Index of the oldest sample, replaced by the next one
+    index: u8,
+    len: u8,
+}
+
+impl<const N: usize, S: Sample> Median<N, S> {
+    pub fn new() -> Self {
+        const {
+            assert!(N % 2 == 1, "Median window must be odd");
+            assert!(N <= u8::MAX as usize, "Median window must be at most 255");
+        }
+        Self {
+            samples: [S::default(); N],
+            index: 0,
+            len: 0,
+        }
+    }
+}
+
+impl<const N: usize, S: Sample> Default for Median<N, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+const MEDIAN_DESCRIPTOR: EffectDescriptor = EffectDescriptor {
+    name: "median",
+    params: &[],
+};
+
+impl<const N: usize, S: Sample> Effect<S> for Median<N, S> {
+    fn update(&mut self, input: S) -> S {
+        let index = self.index as usize;
+        self.samples[index] = input;
+        let len = (self.len as usize + 1).min(N);
+        self.len = len as u8;
+        self.index = ((index + 1) % N) as u8;
+
+        // Until the window is filled, the samples are `samples[..len]`
+        let mut sorted = self.samples;
+        let sorted = &mut sorted[..len];
+        for i in 1..len {
+            let mut j = i;
+            while j > 0 && sorted[j - 1] > sorted[j] {
+                sorted.swap(j - 1, j);
+                j -= 1;
+            }
+        }
+        sorted[(len - 1) / 2]
+    }
+
+    fn reset(&mut self) {
+        self.index = 0;
+        self.len = 0;
+    }
+
+    fn descriptor(&self) -> Option<&'static EffectDescriptor> {
+        Some(&MEDIAN_DESCRIPTOR)
+    }
+}
+
+impl_into_dyn_effect!([const N: usize,] Median<N, S>);
+
+/// One Euro filter: low-pass filter with cutoff frequency rising with the input speed, so it
+/// removes jitter at rest without adding lag in motion. Cutoff is `min_cutoff + beta * speed`,
+/// where speed is in samples per second, low-pass filtered at `d_cutoff`.
+///
+/// `sample_period` is the time between updates in seconds. [OneEuro::filter] takes the elapsed
+/// time instead, for unevenly spaced samples.
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "crate::serialize::OneEuroData")
+)]
+pub struct OneEuro {
+    min_cutoff: f32,
+    beta: f32,
+    d_cutoff: f32,
+    sample_period: f32,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    value: Option<f32>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    speed: f32,
+}
+
+impl OneEuro {
+    /// Creates filter updated every `sample_period` seconds, with speed filtered at `d_cutoff`
+    /// (1 Hz is a good start). Rejects values outside of [OneEuro::DESCRIPTOR] ranges
+    pub fn new(
+        sample_period: f32,
+        min_cutoff: f32,
+        beta: f32,
+        d_cutoff: f32,
+    ) -> Result<Self, AxisError> {
+        let mut filter = Self::default();
+        filter.set("sample_period", sample_period)?;
+        filter.set("min_cutoff", min_cutoff)?;
+        filter.set("beta", beta)?;
+        filter.set("d_cutoff", d_cutoff)?;
+        Ok(filter)
+    }
+
+    pub const DESCRIPTOR: EffectDescriptor = EffectDescriptor {
+        name: "one_euro",
+        params: &[
+            ParamDescriptor::new("min_cutoff", 0.001, 1000.0, 1.0, ParamUnit::Hertz),
+            ParamDescriptor::new("beta", 0.0, 1.0, 0.0, ParamUnit::Factor),
+            ParamDescriptor::new("d_cutoff", 0.001, 1000.0, 1.0, ParamUnit::Hertz),
+            ParamDescriptor::new("sample_period", 0.00001, 1.0, 0.001, ParamUnit::Seconds),
+        ],
+    };
+
+    /// Sets parameter checked against [OneEuro::DESCRIPTOR] ranges
+    fn set(&mut self, name: &str, value: f32) -> Result<(), AxisError> {
+        let param = Self::DESCRIPTOR
+            .param(name)
+            .ok_or(AxisError::UnknownParam)?;
+        check_factor(value, param.min, param.max)?;
+        match name {
+            "min_cutoff" => self.min_cutoff = value,
+            "beta" => self.beta = value,
+            "d_cutoff" => self.d_cutoff = value,
+            _ => self.sample_period = value,
+        }
+        Ok(())
+    }
+
+    /// Filters `input` sampled `dt` seconds after the previous one. Samples with non-positive
+    /// `dt`, e.g. duplicate timestamps, are ignored and the previous output is returned
+    pub fn filter<S: Sample>(&mut self, input: S, dt: f32) -> S {
+        let x = input.to_f32();
+        let value = match self.value {
+            Some(previous) if dt > 0.0 => {
+                let speed = (x - previous) / dt;
+                self.speed += smoothing_factor(self.d_cutoff, dt) * (speed - self.speed);
+                let cutoff = self.min_cutoff + self.beta * self.speed.abs();
+                previous + smoothing_factor(cutoff, dt) * (x - previous)
+            }
+            Some(previous) => previous,
+            None => x,
+        };
+
+        self.value = Some(value);
+        S::from_f32(value.round())
+    }
+}
+
+/// Filter with [OneEuro::DESCRIPTOR] defaults: 1 ms sample period, 1 Hz cutoffs and no speed
+/// coefficient
+impl Default for OneEuro {
+    fn default() -> Self {
+        Self {
+            min_cutoff: 1.0,
+            beta: 0.0,
+            d_cutoff: 1.0,
+            sample_period: 0.001,
+            value: None,
+            speed: 0.0,
+        }
+    }
+}
+
+/// Exponential smoothing factor of low-pass filter with `cutoff` frequency for period `dt`
+fn smoothing_factor(cutoff: f32, dt: f32) -> f32 {
+    let r = 2.0 * core::f32::consts::PI * cutoff * dt;
+    r / (r + 1.0)
+}
+
+impl<S: Sample> Effect<S> for OneEuro {
+    fn update(&mut self, input: S) -> S {
+        self.filter(input, self.sample_period)
+    }
+
+    fn reset(&mut self) {
+        self.value = None;
+        self.speed = 0.0;
+    }
+
+    fn param(&self, name: &str) -> Option<f32> {
+        match name {
+            "min_cutoff" => Some(self.min_cutoff),
+            "beta" => Some(self.beta),
+            "d_cutoff" => Some(self.d_cutoff),
+            "sample_period" => Some(self.sample_period),
+            _ => None,
+        }
+    }
+
+    fn set_param(&mut self, name: &str, value: f32) -> Result<(), AxisError> {
+        self.set(name, value)
+    }
+
+    fn descriptor(&self) -> Option<&'static EffectDescriptor> {
+        Some(const { &Self::DESCRIPTOR })
     }
 }
 
-impl_into_dyn_effect!(Smooth);
+impl_into_dyn_effect!(OneEuro);
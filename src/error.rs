@@ -0,0 +1,73 @@
+use crate::Sample;
+use core::fmt;
+use micromath::F32Ext;
+
+/// Invalid axis or effect configuration
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AxisError {
+    /// `min` is equal to `max`
+    EmptyRange,
+    /// `min` is greater than `max`
+    InvertedBounds,
+    /// Center is outside of `min..=max`
+    CenterOutOfRange,
+    /// Filter factor is out of its allowed range, e.g. [crate::effects::Lerp] factor outside `0..=1`
+    InvalidFactor,
+    /// [crate::Pipeline] has no free slots
+    PipelineFull,
+    /// Index is outside of [crate::Pipeline] effects
+    IndexOutOfBounds,
+    /// Effect has no parameter with such name
+    UnknownParam,
+}
+
+impl fmt::Display for AxisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AxisError::EmptyRange => write!(f, "min is equal to max"),
+            AxisError::InvertedBounds => write!(f, "min is greater than max"),
+            AxisError::CenterOutOfRange => write!(f, "center is outside of min..=max"),
+            AxisError::InvalidFactor => write!(f, "factor is out of allowed range"),
+            AxisError::PipelineFull => write!(f, "pipeline is full"),
+            AxisError::IndexOutOfBounds => write!(f, "effect index is out of bounds"),
+            AxisError::UnknownParam => write!(f, "unknown effect parameter"),
+        }
+    }
+}
+
+impl core::error::Error for AxisError {}
+
+/// Checks that `min..=max` is not empty nor inverted
+pub(crate) fn check_range<S: Sample>(min: S, max: S) -> Result<(), AxisError> {
+    if min == max {
+        Err(AxisError::EmptyRange)
+    } else if min > max {
+        Err(AxisError::InvertedBounds)
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks that `center` is inside of `min..=max`
+pub(crate) fn check_center<S: Sample>(min: S, center: S, max: S) -> Result<(), AxisError> {
+    if center < min || center > max {
+        Err(AxisError::CenterOutOfRange)
+    } else {
+        Ok(())
+    }
+}
+
+/// Converts parameter `value` to sample, rejecting values outside of the sample range
+pub(crate) fn check_sample<S: Sample>(value: f32) -> Result<S, AxisError> {
+    check_factor(value, S::MIN.to_f32(), S::MAX.to_f32())?;
+    Ok(S::from_f32(value.round()))
+}
+
+/// Checks that `factor` is a number inside of `min..=max`
+pub(crate) fn check_factor(factor: f32, min: f32, max: f32) -> Result<(), AxisError> {
+    if (min..=max).contains(&factor) {
+        Ok(())
+    } else {
+        Err(AxisError::InvalidFactor)
+    }
+}
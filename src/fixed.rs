@@ -0,0 +1,772 @@
+//! Fixed-point arithmetic and effects for MCUs without FPU.
+//!
+//! Effects in this module mirror float ones from [crate::effects] and match them within one LSB
+//! for samples up to 16 bits. Intermediates are at most `i64` wide and divisions are replaced
+//! with reciprocals computed when the effect is configured, so `update` only multiplies and
+//! shifts. [crate::effects::Curve] and [crate::effects::Deadzone] are integer-only already.
+
+use crate::{
+    AxisError, DynEffect, Effect, EffectDescriptor, Sample,
+    effects::{
+        LERP_FACTOR, expo_params, find_segment, impl_into_dyn_effect, midpoint, range_param,
+        set_range_param, set_sample_param,
+    },
+    error::check_range,
+};
+use core::ops::{Add, Div, Mul, Neg, Sub};
+use micromath::F32Ext;
+
+/// Fixed-point number with 16 fractional bits stored in `i32` (Q16.16), used for factors and
+/// slopes. Sample values stay integers and are scaled with `i64` intermediates
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fixed(i32);
+
+impl Fixed {
+    pub const FRAC_BITS: u32 = 16;
+    pub const ZERO: Fixed = Fixed(0);
+    pub const ONE: Fixed = Fixed(1 << Self::FRAC_BITS);
+
+    pub const fn from_bits(bits: i32) -> Self {
+        Self(bits)
+    }
+
+    pub const fn to_bits(self) -> i32 {
+        self.0
+    }
+
+    pub const fn from_int(value: i32) -> Self {
+        Self(value << Self::FRAC_BITS)
+    }
+
+    /// `numerator / denominator`, rounded toward negative infinity
+    pub const fn from_ratio(numerator: i32, denominator: i32) -> Self {
+        Self(((numerator as i64) << Self::FRAC_BITS).div_euclid(denominator as i64) as i32)
+    }
+
+    /// Converts from `f32` rounding to nearest, intended for host-side configuration
+    pub fn from_f32(value: f32) -> Self {
+        Self((value * Self::ONE.0 as f32).round() as i32)
+    }
+
+    pub fn to_f32(self) -> f32 {
+        self.0 as f32 / Self::ONE.0 as f32
+    }
+
+    pub const fn floor(self) -> i32 {
+        self.0 >> Self::FRAC_BITS
+    }
+
+    pub const fn ceil(self) -> i32 {
+        -((-self.0) >> Self::FRAC_BITS)
+    }
+
+    /// Rounds half away from zero, like `f32::round`
+    pub const fn round(self) -> i32 {
+        let half = 1 << (Self::FRAC_BITS - 1);
+        if self.0 < 0 {
+            -((-self.0 + half) >> Self::FRAC_BITS)
+        } else {
+            (self.0 + half) >> Self::FRAC_BITS
+        }
+    }
+
+    pub const fn clamp(self, min: Fixed, max: Fixed) -> Self {
+        if self.0 < min.0 {
+            min
+        } else if self.0 > max.0 {
+            max
+        } else {
+            self
+        }
+    }
+
+    pub const fn add(self, rhs: Fixed) -> Self {
+        Self(self.0 + rhs.0)
+    }
+
+    pub const fn sub(self, rhs: Fixed) -> Self {
+        Self(self.0 - rhs.0)
+    }
+
+    pub const fn mul(self, rhs: Fixed) -> Self {
+        Self(((self.0 as i64 * rhs.0 as i64) >> Self::FRAC_BITS) as i32)
+    }
+
+    pub const fn div(self, rhs: Fixed) -> Self {
+        Self(((self.0 as i64) << Self::FRAC_BITS).div_euclid(rhs.0 as i64) as i32)
+    }
+
+    /// `numerator / denominator` of `i64` values, saturating at `Fixed` range
+    const fn saturating_ratio(numerator: i64, denominator: i64) -> Self {
+        let bits = (numerator << Self::FRAC_BITS).div_euclid(denominator);
+        if bits > i32::MAX as i64 {
+            Self(i32::MAX)
+        } else if bits < i32::MIN as i64 {
+            Self(i32::MIN)
+        } else {
+            Self(bits as i32)
+        }
+    }
+}
+
+impl Add for Fixed {
+    type Output = Fixed;
+
+    fn add(self, rhs: Fixed) -> Fixed {
+        Fixed::add(self, rhs)
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Fixed;
+
+    fn sub(self, rhs: Fixed) -> Fixed {
+        Fixed::sub(self, rhs)
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Fixed;
+
+    fn mul(self, rhs: Fixed) -> Fixed {
+        Fixed::mul(self, rhs)
+    }
+}
+
+impl Div for Fixed {
+    type Output = Fixed;
+
+    fn div(self, rhs: Fixed) -> Fixed {
+        Fixed::div(self, rhs)
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Fixed;
+
+    fn neg(self) -> Fixed {
+        Fixed(-self.0)
+    }
+}
+
+/// Fractional bits of ratios returned by [Reciprocal::ratio]
+const RATIO_BITS: u32 = 31;
+const MANTISSA_BITS: u32 = 26;
+
+/// `value * factor >> shift` rounded toward negative infinity. `value` is split, so the result is
+/// exact without `i64` overflow for any sample-sized `value` and `factor` below `2^32`
+const fn mul_shift(value: i64, factor: i64, shift: u32) -> i64 {
+    let low = value & ((1 << shift) - 1);
+    (value >> shift) * factor + ((low * factor) >> shift)
+}
+
+/// Rounds value with [Fixed::FRAC_BITS] fractional bits to integer, half up
+const fn round_frac(bits: i64) -> i64 {
+    (bits + (1 << (Fixed::FRAC_BITS - 1))) >> Fixed::FRAC_BITS
+}
+
+/// Reciprocal of a divisor below `2^33`, computed once so that division by it is a
+/// multiplication and a shift. Holds 26-bit mantissa and the divisor bit length in `u32`
+#[derive(Clone, Copy, Debug, Default)]
+struct Reciprocal(u32);
+
+impl Reciprocal {
+    /// Zero divisor is treated as 1
+    const fn new(divisor: u64) -> Self {
+        let divisor = if divisor == 0 { 1 } else { divisor };
+        let bits = u64::BITS - divisor.leading_zeros();
+        // `divisor >= 2^(bits - 1)`, so the mantissa is below `2^MANTISSA_BITS`
+        let mantissa = ((1 << (MANTISSA_BITS - 1 + bits)) - 1) / divisor;
+        Self(mantissa as u32 | (bits << MANTISSA_BITS))
+    }
+
+    /// `value / divisor` with [RATIO_BITS] fractional bits, `value` must be in `0..=divisor`
+    const fn ratio(self, value: u64) -> i64 {
+        let mantissa = (self.0 & ((1 << MANTISSA_BITS) - 1)) as u64;
+        let bits = self.0 >> MANTISSA_BITS;
+        let product = value * mantissa;
+        // `value / divisor = value * mantissa / 2^(MANTISSA_BITS - 1 + bits)`
+        let shift = MANTISSA_BITS - 1 + bits;
+        if shift >= RATIO_BITS {
+            (product >> (shift - RATIO_BITS)) as i64
+        } else {
+            (product << (RATIO_BITS - shift)) as i64
+        }
+    }
+}
+
+fn check_factor(factor: Fixed, min: Fixed, max: Fixed) -> Result<(), AxisError> {
+    if factor < min || factor > max {
+        Err(AxisError::InvalidFactor)
+    } else {
+        Ok(())
+    }
+}
+
+/// Converts parameter `value` to factor, rejecting NaN
+fn param_factor(value: f32) -> Result<Fixed, AxisError> {
+    if value.is_nan() {
+        return Err(AxisError::InvalidFactor);
+    }
+    Ok(Fixed::from_f32(value))
+}
+
+/// Fixed-point version of [crate::effects::Lerp]
+#[derive(Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "crate::serialize::FixedLerpData")
+)]
+pub struct Lerp {
+    /// Smoothed value with [Fixed::FRAC_BITS] fractional bits
+    #[cfg_attr(feature = "serde", serde(skip))]
+    smoothed_value: i64,
+    lerp_factor: Fixed,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    initialized: bool,
+}
+
+impl Lerp {
+    pub fn new(factor: Fixed) -> Self {
+        Self {
+            smoothed_value: 0,
+            lerp_factor: factor.clamp(-Fixed::ONE, Fixed::ONE),
+            initialized: false,
+        }
+    }
+
+    pub const DESCRIPTOR: EffectDescriptor = EffectDescriptor {
+        name: "fixed_lerp",
+        params: &[LERP_FACTOR],
+    };
+
+    /// Same as [Lerp::new], but rejects factors outside of `0..=1`
+    pub fn try_new(factor: Fixed) -> Result<Self, AxisError> {
+        check_factor(factor, Fixed::ZERO, Fixed::ONE)?;
+        Ok(Self::new(factor))
+    }
+}
+
+impl<S: Sample> Effect<S> for Lerp {
+    fn update(&mut self, value: S) -> S {
+        let value = value.to_i64() << Fixed::FRAC_BITS;
+        let sv = if self.initialized {
+            self.smoothed_value
+        } else {
+            value
+        };
+        let factor = self.lerp_factor.to_bits() as i64;
+        let new_value = sv + mul_shift(value - sv, factor, Fixed::FRAC_BITS);
+
+        self.smoothed_value = new_value;
+        self.initialized = true;
+        S::from_i64(new_value >> Fixed::FRAC_BITS)
+    }
+
+    fn reset(&mut self) {
+        self.initialized = false;
+    }
+
+    fn param(&self, name: &str) -> Option<f32> {
+        match name {
+            "factor" => Some(self.lerp_factor.to_f32()),
+            _ => None,
+        }
+    }
+
+    fn set_param(&mut self, name: &str, value: f32) -> Result<(), AxisError> {
+        match name {
+            "factor" => {
+                self.lerp_factor = Self::try_new(param_factor(value)?)?.lerp_factor;
+                Ok(())
+            }
+            _ => Err(AxisError::UnknownParam),
+        }
+    }
+
+    fn descriptor(&self) -> Option<&'static EffectDescriptor> {
+        Some(const { &Self::DESCRIPTOR })
+    }
+}
+
+impl_into_dyn_effect!(Lerp);
+
+/// Fixed-point version of [crate::effects::Expo]
+///
+/// Keeps reciprocals of both side spans, so it takes 24 bytes for 16-bit samples and needs
+/// e.g. `DynEffect<u16, 24>`
+#[derive(Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "crate::serialize::FixedExpoData<S>")
+)]
+pub struct Expo<S: Sample = u16> {
+    min: S,
+    max: S,
+    center: S,
+    low: Fixed,
+    high: Fixed,
+    /// Reciprocal of `center - min`
+    #[cfg_attr(feature = "serde", serde(skip))]
+    low_scale: Reciprocal,
+    /// Reciprocal of `max - center`
+    #[cfg_attr(feature = "serde", serde(skip))]
+    high_scale: Reciprocal,
+}
+
+impl<S: Sample> Expo<S> {
+    /// Creates symmetric expo for `min..=max` range centered at midpoint, `factor` is in `-1..=1`
+    pub fn new(min: S, max: S, factor: Fixed) -> Self {
+        Self::asymmetric(min, max, factor, factor)
+    }
+
+    /// Creates expo with separate factors for `min..center` (`low`) and `center..max` (`high`) sides
+    pub fn asymmetric(min: S, max: S, low: Fixed, high: Fixed) -> Self {
+        let mut expo = Self {
+            min,
+            max,
+            center: midpoint(min, max),
+            low: low.clamp(-Fixed::ONE, Fixed::ONE),
+            high: high.clamp(-Fixed::ONE, Fixed::ONE),
+            low_scale: Reciprocal::default(),
+            high_scale: Reciprocal::default(),
+        };
+        expo.update_scales();
+        expo
+    }
+
+    pub const DESCRIPTOR: EffectDescriptor = EffectDescriptor {
+        name: "fixed_expo",
+        params: &expo_params::<S>(),
+    };
+
+    /// Same as [Expo::new], but validates the range and rejects factor outside of `-1..=1`
+    pub fn try_new(min: S, max: S, factor: Fixed) -> Result<Self, AxisError> {
+        Self::try_asymmetric(min, max, factor, factor)
+    }
+
+    /// Same as [Expo::asymmetric], but validates the range and factors
+    pub fn try_asymmetric(min: S, max: S, low: Fixed, high: Fixed) -> Result<Self, AxisError> {
+        check_range(min, max)?;
+        check_factor(low, -Fixed::ONE, Fixed::ONE)?;
+        check_factor(high, -Fixed::ONE, Fixed::ONE)?;
+        Ok(Self::asymmetric(min, max, low, high))
+    }
+
+    /// Moves the curve center to `center`
+    pub fn with_center(mut self, center: S) -> Self {
+        self.center = center.clamp(self.min, self.max);
+        self.update_scales();
+        self
+    }
+
+    fn update_scales(&mut self) {
+        let (min, max, center) = (self.min.to_i64(), self.max.to_i64(), self.center.to_i64());
+        self.low_scale = Reciprocal::new(center.saturating_sub(min).max(0) as u64);
+        self.high_scale = Reciprocal::new(max.saturating_sub(center).max(0) as u64);
+    }
+}
+
+/// Fixed-point version of [crate::effects::expo_curve], `x` and the result have [RATIO_BITS]
+/// fractional bits
+fn expo_curve(x: i64, factor: Fixed) -> i64 {
+    let one = 1 << RATIO_BITS;
+    let cube = |t: i64| (((t * t) >> RATIO_BITS) * t) >> RATIO_BITS;
+    let factor = factor.to_bits() as i64;
+    let fixed_one = Fixed::ONE.to_bits() as i64;
+    if factor >= 0 {
+        (factor * cube(x) + (fixed_one - factor) * x) >> Fixed::FRAC_BITS
+    } else {
+        let t = one - x;
+        one - ((-factor * cube(t) + (fixed_one + factor) * t) >> Fixed::FRAC_BITS)
+    }
+}
+
+impl<S: Sample> Effect<S> for Expo<S> {
+    fn update(&mut self, input: S) -> S {
+        let value = input.clamp(self.min, self.max).to_i64();
+        let (min, max, center) = (self.min.to_i64(), self.max.to_i64(), self.center.to_i64());
+        // `span * y` with `Fixed::FRAC_BITS` fractional bits
+        let scale =
+            |span: i64, y: i64| round_frac(mul_shift(span, y, RATIO_BITS - Fixed::FRAC_BITS));
+
+        // End points map to themselves, while reciprocals of 32-bit spans are slightly off
+        if value == min || value == max {
+            return input.clamp(self.min, self.max);
+        }
+
+        let output = if value < center {
+            let x = self.low_scale.ratio((center - value) as u64);
+            center - scale(center - min, expo_curve(x, self.low))
+        } else if value > center {
+            let x = self.high_scale.ratio((value - center) as u64);
+            center + scale(max - center, expo_curve(x, self.high))
+        } else {
+            return self.center;
+        };
+
+        S::from_i64(output)
+    }
+
+    fn param(&self, name: &str) -> Option<f32> {
+        let (low, high) = (self.low.to_f32(), self.high.to_f32());
+        match name {
+            "factor" => Some((low + high) / 2.0),
+            "low" => Some(low),
+            "high" => Some(high),
+            _ => self.param_sample(name).map(S::to_f32),
+        }
+    }
+
+    fn set_param(&mut self, name: &str, value: f32) -> Result<(), AxisError> {
+        if !matches!(name, "factor" | "low" | "high") {
+            return set_sample_param(self, name, value);
+        }
+
+        let factor = param_factor(value)?;
+        let (low, high) = match name {
+            "factor" => (factor, factor),
+            "low" => (factor, self.high),
+            _ => (self.low, factor),
+        };
+
+        let expo = Self::try_asymmetric(self.min, self.max, low, high)?;
+        (self.low, self.high) = (expo.low, expo.high);
+        Ok(())
+    }
+
+    fn param_sample(&self, name: &str) -> Option<S> {
+        range_param(name, self.min, self.max, self.center)
+    }
+
+    fn set_param_sample(&mut self, name: &str, value: S) -> Result<(), AxisError> {
+        set_range_param(name, value, &mut self.min, &mut self.max, &mut self.center)?;
+        self.update_scales();
+        Ok(())
+    }
+
+    fn descriptor(&self) -> Option<&'static EffectDescriptor> {
+        Some(const { &Self::DESCRIPTOR })
+    }
+}
+
+impl_into_dyn_effect!(Expo<S>);
+
+/// Fixed-point version of [crate::effects::Spline]
+///
+/// Tangents saturate at [Fixed] range, which only matters for slopes steeper than 32767
+#[derive(Clone, Copy)]
+pub struct Spline<const N: usize, S: Sample = u16> {
+    points: [(S, S); N],
+    tangents: [Fixed; N],
+    /// Reciprocals of segment widths, the last one is unused
+    scales: [Reciprocal; N],
+}
+
+const SPLINE_DESCRIPTOR: EffectDescriptor = EffectDescriptor {
+    name: "fixed_spline",
+    params: &[],
+};
+
+/// Slope of the segment `points[i]..points[i + 1]`
+const fn secant(points: &[(i64, i64)], i: usize) -> Fixed {
+    let dx = points[i + 1].0 - points[i].0;
+    if dx == 0 {
+        return Fixed::ZERO;
+    }
+    Fixed::saturating_ratio(points[i + 1].1 - points[i].1, dx)
+}
+
+const fn spline_tangent(points: &[(i64, i64)], i: usize) -> Fixed {
+    let n = points.len();
+    if n < 2 {
+        return Fixed::ZERO;
+    }
+    if i == 0 {
+        return secant(points, 0);
+    }
+    if i == n - 1 {
+        return secant(points, n - 2);
+    }
+
+    let d0 = secant(points, i - 1).0 as i64;
+    let d1 = secant(points, i).0 as i64;
+    if d0 == 0 || d1 == 0 || (d0 < 0) != (d1 < 0) {
+        return Fixed::ZERO;
+    }
+
+    // Weighted harmonic mean `3 (h0 + h1) / ((2 h1 + h0) / d0 + (h1 + 2 h0) / d1)`, with weights
+    // normalized to `Fixed` so that wide segments don't overflow
+    let h0 = points[i].0 - points[i - 1].0;
+    let h1 = points[i + 1].0 - points[i].0;
+    let w0 = Fixed::saturating_ratio(2 * h1 + h0, 3 * (h0 + h1)).0 as i64;
+    let w1 = Fixed::ONE.0 as i64 - w0;
+    let denominator = (w0 * d1 + w1 * d0) >> Fixed::FRAC_BITS;
+    Fixed::saturating_ratio((d0 * d1) >> Fixed::FRAC_BITS, denominator)
+}
+
+const fn spline_tables<const N: usize>(points: &[(i64, i64); N]) -> ([Fixed; N], [Reciprocal; N]) {
+    let mut tangents = [Fixed::ZERO; N];
+    let mut scales = [Reciprocal(0); N];
+    let mut i = 0;
+    while i < N {
+        tangents[i] = spline_tangent(points, i);
+        if i + 1 < N && points[i + 1].0 > points[i].0 {
+            scales[i] = Reciprocal::new((points[i + 1].0 - points[i].0) as u64);
+        }
+        i += 1;
+    }
+    (tangents, scales)
+}
+
+impl<const N: usize> Spline<N> {
+    /// Creates `u16` spline, usable in `static` tables
+    pub const fn new(points: [(u16, u16); N]) -> Self {
+        let mut points_i64 = [(0, 0); N];
+        let mut i = 0;
+        while i < N {
+            points_i64[i] = (points[i].0 as i64, points[i].1 as i64);
+            i += 1;
+        }
+
+        let (tangents, scales) = spline_tables(&points_i64);
+        Self {
+            points,
+            tangents,
+            scales,
+        }
+    }
+}
+
+impl<const N: usize, S: Sample> Spline<N, S> {
+    pub fn from_points(points: [(S, S); N]) -> Self {
+        let points_i64 = points.map(|(x, y)| (x.to_i64(), y.to_i64()));
+        let (tangents, scales) = spline_tables(&points_i64);
+        Self {
+            points,
+            tangents,
+            scales,
+        }
+    }
+
+    pub fn points(&self) -> &[(S, S); N] {
+        &self.points
+    }
+
+    /// Evaluates the spline at `input`, values outside of the spline are clamped to the end points
+    pub fn evaluate(&self, input: S) -> S {
+        let i = match find_segment(&self.points, input) {
+            Ok(i) => i,
+            Err(output) => return output,
+        };
+
+        let (x0, y0) = self.points[i];
+        let (x1, y1) = self.points[i + 1];
+        // Same as in `Expo`, exact at the segment end even with 32-bit widths
+        if input == x1 {
+            return y1;
+        }
+
+        let h = x1.to_i64() - x0.to_i64();
+        let t = self.scales[i].ratio((input.to_i64() - x0.to_i64()) as u64);
+        let t2 = (t * t) >> RATIO_BITS;
+        let t3 = (t2 * t) >> RATIO_BITS;
+
+        // Hermite basis in `y0 + h01 (y1 - y0) + h10 h m0 + h11 h m1` form, with
+        // `Fixed::FRAC_BITS` fractional bits in the sum
+        let h01 = 3 * t2 - 2 * t3;
+        let h10 = t3 - 2 * t2 + t;
+        let h11 = t3 - t2;
+        let dy = (y1.to_i64() - y0.to_i64()) << Fixed::FRAC_BITS;
+        let m0 = h * self.tangents[i].to_bits() as i64;
+        let m1 = h * self.tangents[i + 1].to_bits() as i64;
+        let y = (y0.to_i64() << Fixed::FRAC_BITS)
+            + mul_shift(dy, h01, RATIO_BITS)
+            + mul_shift(m0, h10, RATIO_BITS)
+            + mul_shift(m1, h11, RATIO_BITS);
+
+        S::from_i64(round_frac(y)).clamp(y0.min(y1), y0.max(y1))
+    }
+}
+
+impl<const N: usize, S: Sample> Effect<S> for Spline<N, S> {
+    fn update(&mut self, input: S) -> S {
+        self.evaluate(input)
+    }
+
+    fn descriptor(&self) -> Option<&'static EffectDescriptor> {
+        Some(&SPLINE_DESCRIPTOR)
+    }
+}
+
+impl<const N: usize, S: Sample> Effect<S> for &'static Spline<N, S> {
+    fn update(&mut self, input: S) -> S {
+        self.evaluate(input)
+    }
+
+    fn descriptor(&self) -> Option<&'static EffectDescriptor> {
+        Some(&SPLINE_DESCRIPTOR)
+    }
+}
+
+impl_into_dyn_effect!([const N: usize,] Spline<N, S>);
+
+impl_into_dyn_effect!([const N: usize,] &'static Spline<N, S>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Axis, effects};
+
+    fn assert_within_lsb(fixed: u16, float: u16) {
+        assert!(fixed.abs_diff(float) <= 1, "fixed {fixed}, float {float}");
+    }
+
+    #[test]
+    fn fixed_arithmetic() {
+        let half = Fixed::from_ratio(1, 2);
+        assert_eq!(half * Fixed::from_int(7), Fixed::from_ratio(7, 2));
+        assert_eq!(Fixed::from_ratio(7, 2).round(), 4);
+        assert_eq!(Fixed::from_ratio(-7, 2).round(), -4);
+        assert_eq!(Fixed::from_ratio(-7, 2).floor(), -4);
+        assert_eq!(
+            Fixed::from_int(3) / Fixed::from_int(4),
+            Fixed::from_ratio(3, 4)
+        );
+        assert_eq!(Fixed::from_f32(0.25), Fixed::from_ratio(1, 4));
+    }
+
+    #[test]
+    fn effect_validation() {
+        assert_eq!(
+            Lerp::try_new(Fixed::from_int(2)).err(),
+            Some(AxisError::InvalidFactor)
+        );
+        assert!(Lerp::try_new(Fixed::ONE).is_ok());
+        assert_eq!(
+            Expo::try_new(0u16, 0, Fixed::ZERO).err(),
+            Some(AxisError::EmptyRange)
+        );
+        assert_eq!(
+            Expo::try_asymmetric(0u16, 10, Fixed::ZERO, -Fixed::from_int(2)).err(),
+            Some(AxisError::InvalidFactor)
+        );
+    }
+
+    #[test]
+    fn effect_params() {
+        let mut lerp: DynEffect = Lerp::new(Fixed::ONE).into();
+        lerp.set_param("factor", 0.5).unwrap();
+        assert_eq!(lerp.param("factor"), Some(0.5));
+        assert_eq!(
+            lerp.set_param("factor", f32::NAN),
+            Err(AxisError::InvalidFactor)
+        );
+
+        let mut expo: DynEffect<u16, 24> = Expo::new(0, 200, Fixed::ZERO).into();
+        expo.set_param("low", -0.5).unwrap();
+        assert_eq!(expo.param("factor"), Some(-0.25));
+        assert_eq!(expo.set_param("high", 2.0), Err(AxisError::InvalidFactor));
+        assert_eq!(expo.set_param("gain", 0.0), Err(AxisError::UnknownParam));
+    }
+
+    #[test]
+    fn effects_in_chain() {
+        let mut axis = Axis::new(0u16, 200, false).with_effect_size::<24>();
+        let mut effects: [DynEffect<u16, 24>; 2] = [
+            Lerp::new(Fixed::from_ratio(1, 2)).into(),
+            Expo::new(0, 200, Fixed::from_ratio(1, 2)).into(),
+        ];
+        axis.update(0, effects.iter_mut());
+        axis.update(200, effects.iter_mut());
+        assert_eq!(axis.output_fixed(0, 200), 100);
+    }
+
+    #[test]
+    fn output_matches_float() {
+        for (min, center, max) in [(0, 2048, 4095), (100, 1500, 4000), (0, 30000, 65535)] {
+            for reversed in [false, true] {
+                let mut linear = Axis::new(min, max, reversed);
+                let mut centered = Axis::new_centered(min, center, max, reversed);
+                for value in (min..=max).step_by(7) {
+                    linear.update(value, []);
+                    centered.update(value, []);
+                    for (range_min, range_max) in [(0, 255), (0, 65535), (1000, 2000)] {
+                        assert_within_lsb(
+                            linear.output_fixed(range_min, range_max),
+                            linear.output(range_min, range_max),
+                        );
+                        assert_within_lsb(
+                            centered.output_fixed(range_min, range_max),
+                            centered.output(range_min, range_max),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn lerp_matches_float() {
+        for factor in [0.1, 0.5, 0.9] {
+            let mut fixed = Lerp::new(Fixed::from_f32(factor));
+            let mut float = effects::Lerp::new(factor);
+            for i in 0..1000u32 {
+                let input = ((i * 7919) % 65536) as u16;
+                assert_within_lsb(fixed.update(input), float.update(input));
+            }
+        }
+    }
+
+    #[test]
+    fn expo_matches_float() {
+        for factor in [-1.0, -0.3, 0.0, 0.3, 1.0] {
+            let mut fixed = Expo::new(0u16, 65535, Fixed::from_f32(factor)).with_center(30000);
+            let mut float = effects::Expo::new(0u16, 65535, factor).with_center(30000);
+            for input in (0..=65535).step_by(13) {
+                assert_within_lsb(fixed.update(input), float.update(input));
+            }
+        }
+    }
+
+    #[test]
+    fn spline_matches_float() {
+        let points = [
+            (0, 0),
+            (1000, 100),
+            (20000, 30000),
+            (40000, 31000),
+            (65535, 65535),
+        ];
+        let fixed = Spline::new(points);
+        let float = effects::Spline::new(points);
+        for input in (0..=65535).step_by(11) {
+            assert_within_lsb(fixed.evaluate(input), float.evaluate(input));
+        }
+    }
+
+    #[test]
+    fn full_range_samples() {
+        let mut lerp = Lerp::new(Fixed::from_ratio(1, 2));
+        assert_eq!(Effect::<u32>::update(&mut lerp, u32::MAX), u32::MAX);
+        assert_eq!(Effect::<u32>::update(&mut lerp, 0), u32::MAX / 2);
+
+        let mut expo = Expo::new(i32::MIN, i32::MAX, Fixed::from_ratio(1, 2));
+        assert_eq!(expo.update(i32::MIN), i32::MIN);
+        assert_eq!(expo.update(i32::MAX), i32::MAX);
+        let mut float = effects::Expo::new(i32::MIN, i32::MAX, 0.5);
+        let (fixed, float) = (expo.update(1 << 30), float.update(1 << 30));
+        assert!(
+            fixed.abs_diff(float) < 1 << 8,
+            "fixed {fixed}, float {float}"
+        );
+
+        let spline = Spline::from_points([(0, 0), (1 << 31, 100), (u32::MAX, u32::MAX)]);
+        assert_eq!(spline.evaluate(u32::MAX), u32::MAX);
+        let values = (0..=64).map(|i| spline.evaluate((u32::MAX / 64) * i));
+        assert!(values.clone().zip(values.skip(1)).all(|(a, b)| a <= b));
+    }
+}
@@ -2,82 +2,606 @@
 #![allow(unused_imports)]
 use core::mem::MaybeUninit;
 use micromath::F32Ext;
+pub mod builder;
+pub mod calibration;
+pub mod chain;
+pub mod descriptor;
+pub mod dsl;
 pub mod effects;
+pub mod error;
+pub mod fixed;
+pub mod pipeline;
+pub mod registry;
+pub mod sample;
+#[cfg(feature = "serde")]
+mod serialize;
+pub mod stick;
 
-/// Max [Effect] size in bytes that can be fit in [DynEffect]
+pub use builder::AxisBuilder;
+pub use calibration::{AutoCalibration, Calibration};
+pub use chain::{Bypass, Chain, EffectChain};
+pub use descriptor::{EffectDescriptor, ParamDescriptor, ParamUnit};
+pub use error::AxisError;
+pub use fixed::Fixed;
+pub use pipeline::{Pipeline, PipelineAxis};
+pub use registry::Registry;
+pub use sample::Sample;
+pub use stick::Stick;
+
+/// Default inline storage size of [DynEffect] in bytes, max [Effect] size that fits in it
 pub const MAX_EFFECT_SIZE: usize = 16;
 
-/// Effect trait. If you want to implement custom [Effect] make sure the struct fits in [MAX_EFFECT_SIZE]
-pub trait Effect {
-    fn update(&mut self, input: u16) -> u16;
+/// Max [Effect] alignment in bytes that can be fit in [DynEffect]
+pub const MAX_EFFECT_ALIGN: usize = 8;
+
+/// Effect trait. If you want to implement custom [Effect] make sure the struct fits in
+/// [DynEffect] storage size ([MAX_EFFECT_SIZE] by default) and [MAX_EFFECT_ALIGN], otherwise
+/// wrapping it in [DynEffect] fails to compile. [DynEffect] also requires the effect to be
+/// `Clone + Send + Sync + 'static`
+pub trait Effect<S: Sample = u16> {
+    fn update(&mut self, input: S) -> S;
+
+    /// Clears runtime state (e.g. smoothed value) keeping the configuration,
+    /// call it after re-calibration
+    fn reset(&mut self) {}
+
+    /// Whether the effect processes input, `false` for bypassed [DynEffect]s and [Bypass]es
+    fn is_enabled(&self) -> bool {
+        true
+    }
+
+    /// Reads numeric parameter `name`, `None` if the effect has no such parameter. `f32` holds
+    /// integers exactly only up to 2^24, use [Effect::param_sample] for sample values of `u32`
+    /// and `i32` effects
+    fn param(&self, _name: &str) -> Option<f32> {
+        None
+    }
+
+    /// Sets numeric parameter `name`, fails with [AxisError::UnknownParam] if the effect
+    /// has no such parameter or with the validation error of the value
+    fn set_param(&mut self, _name: &str, _value: f32) -> Result<(), AxisError> {
+        Err(AxisError::UnknownParam)
+    }
+
+    /// Reads [ParamUnit::Sample] or [ParamUnit::Distance] parameter `name` without loss of
+    /// precision, `None` for other parameters. By default converts [Effect::param] of such
+    /// parameters of [Effect::descriptor]
+    fn param_sample(&self, name: &str) -> Option<S> {
+        if !self.descriptor()?.param(name)?.unit.is_sample() {
+            return None;
+        }
+        self.param(name).map(|value| S::from_f32(value.round()))
+    }
+
+    /// Sets [ParamUnit::Sample] or [ParamUnit::Distance] parameter `name` without loss of
+    /// precision, fails with [AxisError::UnknownParam] for other parameters. By default goes
+    /// through [Effect::set_param] like [Effect::param_sample]
+    fn set_param_sample(&mut self, name: &str, value: S) -> Result<(), AxisError> {
+        let is_sample = self
+            .descriptor()
+            .and_then(|descriptor| descriptor.param(name))
+            .is_some_and(|param| param.unit.is_sample());
+        if !is_sample {
+            return Err(AxisError::UnknownParam);
+        }
+        self.set_param(name, value.to_f32())
+    }
+
+    /// Type name and parameters of the effect, `None` for effects without descriptor
+    fn descriptor(&self) -> Option<&'static EffectDescriptor> {
+        None
+    }
 }
 
-/// Dynamic dispatching wrapper for [Effect] trait.
-#[derive(Clone)]
-pub struct DynEffect {
-    data: [MaybeUninit<u8>; MAX_EFFECT_SIZE],
-    apply_fn: fn(&mut [MaybeUninit<u8>; MAX_EFFECT_SIZE], u16) -> u16,
+/// Inline effect storage of `N` bytes aligned to [MAX_EFFECT_ALIGN]
+#[repr(C, align(8))]
+pub(crate) struct Storage<const N: usize>([MaybeUninit<u8>; N]);
+
+const _: () = assert!(core::mem::align_of::<Storage<0>>() == MAX_EFFECT_ALIGN);
+
+impl<const N: usize> Storage<N> {
+    fn new<T>(effect: T) -> Self {
+        let mut data = Self([MaybeUninit::uninit(); N]);
+        unsafe {
+            let ptr = data.0.as_mut_ptr() as *mut T;
+            ptr.write(effect);
+        }
+        data
+    }
+}
+
+/// Methods of the effect stored in [ErasedEffect]: clone and drop glue, `update` and `M` with
+/// methods specific to the wrapper. One `&'static` table per effect type keeps wrappers small
+pub(crate) struct Vtable<I, M, const N: usize> {
+    clone_fn: unsafe fn(&Storage<N>) -> Storage<N>,
+    drop_fn: unsafe fn(&mut Storage<N>),
+    apply_fn: fn(&mut Storage<N>, I) -> I,
+    methods: M,
+}
+
+impl<I, M, const N: usize> Vtable<I, M, N> {
+    /// Vtable of `T`, `apply_fn` and `methods` are called with storage containing `T`
+    pub(crate) const fn new<T: Clone>(apply_fn: fn(&mut Storage<N>, I) -> I, methods: M) -> Self {
+        Self {
+            clone_fn: clone_erased::<T, N>,
+            drop_fn: drop_erased::<T, N>,
+            apply_fn,
+            methods,
+        }
+    }
+}
+
+unsafe fn clone_erased<T: Clone, const N: usize>(data: &Storage<N>) -> Storage<N> {
+    let effect = unsafe { erased_ref::<T, N>(data) };
+    Storage::new(effect.clone())
 }
 
-impl DynEffect {
-    pub(crate) fn new<T: Effect>(effect: T) -> Self {
-        let real_size = core::mem::size_of::<T>();
+unsafe fn drop_erased<T, const N: usize>(data: &mut Storage<N>) {
+    unsafe { core::ptr::drop_in_place(data.0.as_mut_ptr() as *mut T) }
+}
+
+/// Whether `T` fits in `N` bytes of [DynEffect] storage
+pub const fn fits<T, const N: usize>() -> bool {
+    core::mem::size_of::<T>() <= N && core::mem::align_of::<T>() <= MAX_EFFECT_ALIGN
+}
 
-        if real_size > MAX_EFFECT_SIZE {
-            panic!(
-                "Size of effect is {real_size} bytes this is more than {MAX_EFFECT_SIZE} bytes allowed."
+/// Type-erased inline effect storage, shared by [DynEffect] and [stick::DynStickEffect].
+/// Stored effect is cloned with its own [Clone] and dropped with [ErasedEffect]. Storage bytes
+/// are `Send + Sync` regardless of the effect, so only `Send + Sync` effects are accepted
+pub(crate) struct ErasedEffect<I: 'static, M: 'static, const N: usize> {
+    data: Storage<N>,
+    vtable: &'static Vtable<I, M, N>,
+}
+
+impl<I, M, const N: usize> ErasedEffect<I, M, N> {
+    /// Stores `effect`
+    ///
+    /// # Safety
+    /// `vtable` must be created with [Vtable::new] for `T`
+    pub(crate) unsafe fn new<T: Clone + Send + Sync + 'static>(
+        effect: T,
+        vtable: &'static Vtable<I, M, N>,
+    ) -> Self {
+        const {
+            assert!(
+                core::mem::size_of::<T>() <= N,
+                "Effect does not fit in DynEffect, its size is greater than the storage size"
+            );
+            assert!(
+                core::mem::align_of::<T>() <= MAX_EFFECT_ALIGN,
+                "Effect does not fit in DynEffect, its alignment is greater than MAX_EFFECT_ALIGN"
             );
         }
 
-        let mut data = [MaybeUninit::uninit(); MAX_EFFECT_SIZE];
-        unsafe {
-            let ptr = data.as_mut_ptr() as *mut T;
-            ptr.write(effect);
+        // Safety: `T` fits as checked above
+        unsafe { Self::new_unchecked(effect, vtable) }
+    }
+
+    /// Same as [ErasedEffect::new] without the compile-time check, for generic code that is
+    /// instantiated for effects that don't fit but never calls it with them
+    ///
+    /// # Safety
+    /// `T` must fit in the storage, see [fits], and `vtable` must be created for `T`
+    pub(crate) unsafe fn new_unchecked<T: Clone + Send + Sync + 'static>(
+        effect: T,
+        vtable: &'static Vtable<I, M, N>,
+    ) -> Self {
+        Self {
+            data: Storage::new(effect),
+            vtable,
         }
+    }
+
+    pub(crate) fn update(&mut self, input: I) -> I {
+        (self.vtable.apply_fn)(&mut self.data, input)
+    }
+}
+
+impl<I, M, const N: usize> Clone for ErasedEffect<I, M, N> {
+    fn clone(&self) -> Self {
+        Self {
+            data: unsafe { (self.vtable.clone_fn)(&self.data) },
+            vtable: self.vtable,
+        }
+    }
+}
+
+impl<I, M, const N: usize> Drop for ErasedEffect<I, M, N> {
+    fn drop(&mut self) {
+        unsafe { (self.vtable.drop_fn)(&mut self.data) }
+    }
+}
+
+/// # Safety
+/// `data` must contain `T` written by [ErasedEffect::new]
+pub(crate) unsafe fn erased_mut<T, const N: usize>(data: &mut Storage<N>) -> &mut T {
+    unsafe { &mut *(data.0.as_mut_ptr() as *mut T) }
+}
 
-        fn call<T: Effect, const N: usize>(data: &mut [MaybeUninit<u8>; N], input: u16) -> u16 {
-            let ptr = data.as_mut_ptr() as *mut T;
-            unsafe { (*ptr).update(input) }
+/// # Safety
+/// `data` must contain `T` written by [ErasedEffect::new]
+pub(crate) unsafe fn erased_ref<T, const N: usize>(data: &Storage<N>) -> &T {
+    unsafe { &*(data.0.as_ptr() as *const T) }
+}
+
+/// Rounding mode of [Axis] output
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Rounding {
+    #[default]
+    Floor,
+    /// Rounds half away from zero
+    Nearest,
+    Ceil,
+}
+
+impl Rounding {
+    fn round(self, value: f32) -> f32 {
+        match self {
+            Rounding::Floor => value.floor(),
+            Rounding::Nearest => value.round(),
+            Rounding::Ceil => value.ceil(),
         }
+    }
+
+    /// Rounds `integer + remainder / divisor`, `remainder` is in `0..divisor`
+    fn round_ratio(self, integer: i64, remainder: i64, divisor: i64) -> i64 {
+        match self {
+            Rounding::Floor => integer,
+            Rounding::Nearest => {
+                let twice = 2 * remainder;
+                if twice > divisor || (twice == divisor && integer >= 0) {
+                    integer + 1
+                } else {
+                    integer
+                }
+            }
+            Rounding::Ceil => integer + (remainder > 0) as i64,
+        }
+    }
+}
+
+/// Maps `position` in `-1.0..=1.0` to `range_min..=range_max` with `rounding`
+fn map_position<T: Sample>(position: f32, range_min: T, range_max: T, rounding: Rounding) -> T {
+    let half = (range_max.to_i64() - range_min.to_i64()) as f32 / 2.0;
+    T::from_f32(rounding.round(range_min.to_f32() + half + position * half))
+}
+
+/// Dynamic dispatching wrapper for [Effect] trait, stores the effect inline in `N` bytes.
+///
+/// Larger storage fits larger effects, e.g. `DynEffect<u16, 64>` for filters with sample buffers.
+/// Effects larger than `N` are rejected at compile time:
+///
+/// ```compile_fail
+/// use axis::{DynEffect, Effect, MAX_EFFECT_SIZE};
+///
+/// #[derive(Clone)]
+/// struct Large([u8; MAX_EFFECT_SIZE + 1]);
+///
+/// impl Effect for Large {
+///     fn update(&mut self, input: u16) -> u16 {
+///         input
+///     }
+/// }
+///
+/// let effect: DynEffect = DynEffect::new(Large([0; MAX_EFFECT_SIZE + 1]));
+/// ```
+///
+/// So are effects aligned to more than [MAX_EFFECT_ALIGN]:
+///
+/// ```compile_fail
+/// use axis::{DynEffect, Effect};
+///
+/// #[derive(Clone)]
+/// #[repr(align(16))]
+/// struct Aligned(u16);
+///
+/// impl Effect for Aligned {
+///     fn update(&mut self, input: u16) -> u16 {
+///         input.max(self.0)
+///     }
+/// }
+///
+/// let effect: DynEffect = DynEffect::new(Aligned(0));
+/// ```
+///
+/// [DynEffect] is `Send + Sync`, so effects that are not are rejected too:
+///
+/// ```compile_fail
+/// use axis::{DynEffect, Effect};
+/// use std::rc::Rc;
+///
+/// #[derive(Clone)]
+/// struct Shared(Rc<u16>);
+///
+/// impl Effect for Shared {
+///     fn update(&mut self, input: u16) -> u16 {
+///         input.max(*self.0)
+///     }
+/// }
+///
+/// let effect: DynEffect = DynEffect::new(Shared(Rc::new(0)));
+/// ```
+///
+/// ```compile_fail
+/// use axis::{DynEffect, Effect};
+/// use core::cell::Cell;
+///
+/// #[derive(Clone)]
+/// struct Counter(Cell<u16>);
+///
+/// impl Effect for Counter {
+///     fn update(&mut self, input: u16) -> u16 {
+///         input
+///     }
+///
+///     fn param(&self, _name: &str) -> Option<f32> {
+///         self.0.set(self.0.get() + 1);
+///         None
+///     }
+/// }
+///
+/// let effect: DynEffect = DynEffect::new(Counter(Cell::new(0)));
+/// ```
+#[derive(Clone)]
+pub struct DynEffect<S: Sample = u16, const N: usize = MAX_EFFECT_SIZE> {
+    effect: ErasedEffect<S, EffectMethods<S, N>, N>,
+    enabled: bool,
+}
+
+/// [Effect] methods of the effect stored in [DynEffect], besides `update`
+struct EffectMethods<S, const N: usize> {
+    reset_fn: fn(&mut Storage<N>),
+    param_fn: fn(&Storage<N>, &str) -> Option<f32>,
+    set_param_fn: fn(&mut Storage<N>, &str, f32) -> Result<(), AxisError>,
+    param_sample_fn: fn(&Storage<N>, &str) -> Option<S>,
+    set_param_sample_fn: fn(&mut Storage<N>, &str, S) -> Result<(), AxisError>,
+    descriptor_fn: fn(&Storage<N>) -> Option<&'static EffectDescriptor>,
+}
+
+fn call_effect<S: Sample, T: Effect<S>, const N: usize>(data: &mut Storage<N>, input: S) -> S {
+    unsafe { erased_mut::<T, N>(data).update(input) }
+}
+
+fn reset_effect<S: Sample, T: Effect<S>, const N: usize>(data: &mut Storage<N>) {
+    unsafe { erased_mut::<T, N>(data).reset() }
+}
+
+fn effect_param<S: Sample, T: Effect<S>, const N: usize>(
+    data: &Storage<N>,
+    name: &str,
+) -> Option<f32> {
+    unsafe { erased_ref::<T, N>(data).param(name) }
+}
+
+fn set_effect_param<S: Sample, T: Effect<S>, const N: usize>(
+    data: &mut Storage<N>,
+    name: &str,
+    value: f32,
+) -> Result<(), AxisError> {
+    unsafe { erased_mut::<T, N>(data).set_param(name, value) }
+}
+
+fn effect_param_sample<S: Sample, T: Effect<S>, const N: usize>(
+    data: &Storage<N>,
+    name: &str,
+) -> Option<S> {
+    unsafe { erased_ref::<T, N>(data).param_sample(name) }
+}
+
+fn set_effect_param_sample<S: Sample, T: Effect<S>, const N: usize>(
+    data: &mut Storage<N>,
+    name: &str,
+    value: S,
+) -> Result<(), AxisError> {
+    unsafe { erased_mut::<T, N>(data).set_param_sample(name, value) }
+}
+
+fn effect_descriptor<S: Sample, T: Effect<S>, const N: usize>(
+    data: &Storage<N>,
+) -> Option<&'static EffectDescriptor> {
+    unsafe { erased_ref::<T, N>(data).descriptor() }
+}
 
+impl<S: Sample, const N: usize> DynEffect<S, N> {
+    pub fn new<T: Effect<S> + Clone + Send + Sync + 'static>(effect: T) -> Self {
         Self {
-            data,
-            apply_fn: call::<T, MAX_EFFECT_SIZE>,
+            // Safety: the vtable is created for `T`
+            effect: unsafe { ErasedEffect::new(effect, Self::vtable::<T>()) },
+            enabled: true,
+        }
+    }
+
+    /// Same as [DynEffect::new], but returns `None` instead of failing to compile if the effect
+    /// doesn't fit, for code generic over the storage size
+    pub fn try_new<T: Effect<S> + Clone + Send + Sync + 'static>(effect: T) -> Option<Self> {
+        if !fits::<T, N>() {
+            return None;
+        }
+
+        Some(Self {
+            // Safety: `T` fits as checked above, the vtable is created for `T`
+            effect: unsafe { ErasedEffect::new_unchecked(effect, Self::vtable::<T>()) },
+            enabled: true,
+        })
+    }
+
+    fn vtable<T: Effect<S> + Clone>() -> &'static Vtable<S, EffectMethods<S, N>, N> {
+        const {
+            &Vtable::new::<T>(
+                call_effect::<S, T, N>,
+                EffectMethods {
+                    reset_fn: reset_effect::<S, T, N>,
+                    param_fn: effect_param::<S, T, N>,
+                    set_param_fn: set_effect_param::<S, T, N>,
+                    param_sample_fn: effect_param_sample::<S, T, N>,
+                    set_param_sample_fn: set_effect_param_sample::<S, T, N>,
+                    descriptor_fn: effect_descriptor::<S, T, N>,
+                },
+            )
+        }
+    }
+
+    /// Processes `input`, returns it unchanged if the effect is bypassed
+    pub fn update(&mut self, input: S) -> S {
+        if self.enabled {
+            self.effect.update(input)
+        } else {
+            input
         }
     }
 
-    pub fn update(&mut self, input: u16) -> u16 {
-        (self.apply_fn)(&mut self.data, input)
+    /// See [Effect::reset]
+    pub fn reset(&mut self) {
+        (self.effect.vtable.methods.reset_fn)(&mut self.effect.data)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enables or bypasses the effect
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// See [Effect::param]
+    pub fn param(&self, name: &str) -> Option<f32> {
+        (self.effect.vtable.methods.param_fn)(&self.effect.data, name)
+    }
+
+    /// See [Effect::set_param]
+    pub fn set_param(&mut self, name: &str, value: f32) -> Result<(), AxisError> {
+        (self.effect.vtable.methods.set_param_fn)(&mut self.effect.data, name, value)
+    }
+
+    /// See [Effect::param_sample]
+    pub fn param_sample(&self, name: &str) -> Option<S> {
+        (self.effect.vtable.methods.param_sample_fn)(&self.effect.data, name)
+    }
+
+    /// See [Effect::set_param_sample]
+    pub fn set_param_sample(&mut self, name: &str, value: S) -> Result<(), AxisError> {
+        (self.effect.vtable.methods.set_param_sample_fn)(&mut self.effect.data, name, value)
+    }
+
+    /// See [Effect::descriptor]
+    pub fn descriptor(&self) -> Option<&'static EffectDescriptor> {
+        (self.effect.vtable.methods.descriptor_fn)(&self.effect.data)
+    }
+}
+
+impl<S: Sample, const N: usize> Effect<S> for DynEffect<S, N> {
+    fn update(&mut self, input: S) -> S {
+        DynEffect::update(self, input)
+    }
+
+    fn reset(&mut self) {
+        DynEffect::reset(self)
+    }
+
+    fn is_enabled(&self) -> bool {
+        DynEffect::is_enabled(self)
+    }
+
+    fn param(&self, name: &str) -> Option<f32> {
+        DynEffect::param(self, name)
+    }
+
+    fn set_param(&mut self, name: &str, value: f32) -> Result<(), AxisError> {
+        DynEffect::set_param(self, name, value)
+    }
+
+    fn param_sample(&self, name: &str) -> Option<S> {
+        DynEffect::param_sample(self, name)
+    }
+
+    fn set_param_sample(&mut self, name: &str, value: S) -> Result<(), AxisError> {
+        DynEffect::set_param_sample(self, name, value)
+    }
+
+    fn descriptor(&self) -> Option<&'static EffectDescriptor> {
+        DynEffect::descriptor(self)
     }
 }
 
-pub struct Axis {
-    pub min: u16,
-    pub max: u16,
+/// Input axis, `N` is the storage size of [DynEffect]s in its chain
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "crate::serialize::AxisData<S>")
+)]
+pub struct Axis<S: Sample = u16, const N: usize = MAX_EFFECT_SIZE> {
+    pub min: S,
+    pub max: S,
+    /// Mechanical center. When set, `min..center` and `center..max` halves are scaled separately
+    pub center: Option<S>,
     pub reversed: bool,
-    pub step_filter_factor: u16,
-    old_value: u16,
-    value: u16,
+    pub step_filter_factor: S,
+    /// Rounding mode of output methods
+    pub rounding: Rounding,
+    /// Learns `min`/`max` from observed values when set
+    pub auto_calibration: Option<AutoCalibration<S>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    old_value: S,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    value: S,
 }
 
-impl<'a> Axis {
-    pub fn new(min: u16, max: u16, reversed: bool) -> Self {
+impl<S: Sample> Axis<S> {
+    pub fn new(min: S, max: S, reversed: bool) -> Self {
         Self {
             min,
             max,
+            center: None,
             reversed,
-            step_filter_factor: 0,
+            step_filter_factor: S::default(),
+            rounding: Rounding::Floor,
+            auto_calibration: None,
             old_value: min,
             value: min,
         }
     }
 
-    fn step_filter(&mut self, value: u16) -> u16 {
-        if self.step_filter_factor == 0 {
-            return value
+    /// Creates validating builder for `min..=max` axis
+    pub fn builder(min: S, max: S) -> AxisBuilder<S> {
+        AxisBuilder::new(min, max)
+    }
+
+    /// Creates center-calibrated (bipolar) axis, `center` reading maps to the middle of output range
+    pub fn new_centered(min: S, center: S, max: S, reversed: bool) -> Self {
+        Self {
+            center: Some(center),
+            old_value: center,
+            value: center,
+            ..Self::new(min, max, reversed)
         }
+    }
 
-        if (value as i16 - self.old_value as i16).abs() >= self.step_filter_factor as i16 {
+    /// Converts the axis to accept chains of `DynEffect<S, M>`
+    pub fn with_effect_size<const M: usize>(self) -> Axis<S, M> {
+        Axis {
+            min: self.min,
+            max: self.max,
+            center: self.center,
+            reversed: self.reversed,
+            step_filter_factor: self.step_filter_factor,
+            rounding: self.rounding,
+            auto_calibration: self.auto_calibration,
+            old_value: self.old_value,
+            value: self.value,
+        }
+    }
+}
+
+impl<S: Sample, const N: usize> Axis<S, N> {
+    fn step_filter(&mut self, value: S) -> S {
+        let factor = self.step_filter_factor.to_i64();
+        if factor == 0 {
+            return value;
+        }
+
+        if (value.to_i64() - self.old_value.to_i64()).abs() >= factor {
             self.old_value = value;
             value
         } else {
@@ -85,33 +609,158 @@ impl<'a> Axis {
         }
     }
 
-    fn output_ranged(&self) -> u16 {
+    fn output_ranged(&self) -> S {
         let mut normalized = self.value;
         if self.reversed {
-            normalized = self.max.saturating_sub(self.value.saturating_sub(self.min));
+            normalized =
+                S::from_i64(self.max.to_i64() - (self.value.to_i64() - self.min.to_i64()).max(0));
         }
         normalized.clamp(self.min, self.max)
     }
 
-    pub fn update<I: IntoIterator<Item = &'a mut DynEffect>>(&mut self, value: u16, chain: I) {
-        self.value = self.step_filter(value);
-        for filter in chain {
-            self.value = filter.update(self.value);
+    /// Enables auto-calibration, see [AutoCalibration]
+    pub fn enable_auto_calibration(&mut self, startup_samples: u16, min_travel: S) {
+        self.auto_calibration = Some(AutoCalibration::new(startup_samples, min_travel));
+    }
+
+    fn calibrate(&mut self, value: S) {
+        if let Some(calibration) = &mut self.auto_calibration {
+            if calibration.is_frozen() {
+                return;
+            }
+            calibration.observe(value);
+            if let Some((min, max)) = calibration.trusted_range() {
+                self.min = min;
+                self.max = max;
+            }
         }
     }
 
+    /// Processes `value` through the chain, either an iterator over [DynEffect]s
+    /// or a statically composed [Chain]
+    pub fn update<C: EffectChain<S, N>>(&mut self, value: S, chain: C) {
+        self.calibrate(value);
+        self.value = chain.apply(self.step_filter(value));
+    }
+
+    /// Position relative to center as `numerator / denominator` in `-1..=1` range,
+    /// each half is scaled on its own
+    fn centered_ratio(&self, center: S) -> (i64, i64) {
+        let value = self.value.clamp(self.min, self.max).to_i64();
+        let (min, center, max) = (self.min.to_i64(), center.to_i64(), self.max.to_i64());
+        let (numerator, denominator) = if value < center {
+            (value - center, center - min)
+        } else if value > center {
+            (value - center, max - center)
+        } else {
+            (0, 1)
+        };
+
+        if self.reversed {
+            (-numerator, denominator)
+        } else {
+            (numerator, denominator)
+        }
+    }
+
+    /// Position relative to center in `-1.0..=1.0` range, each half is scaled on its own
+    fn centered_position(&self, center: S) -> f32 {
+        let (numerator, denominator) = self.centered_ratio(center);
+        numerator as f32 / denominator as f32
+    }
+
+    /// Normalized position in `-1.0..=1.0` range, `0.0` is the center
+    /// (or the midpoint of `min..max` if the axis is not center-calibrated).
+    /// Empty `min..max` range maps to `-1.0`, like [Axis::output_in] maps it to `range_min`
+    pub fn output_normalized(&self) -> f32 {
+        match self.center {
+            Some(center) => self.centered_position(center),
+            None => {
+                let span = self.max.to_i64() - self.min.to_i64();
+                if span <= 0 {
+                    return -1.0;
+                }
+                (self.output_ranged().to_i64() - self.min.to_i64()) as f32 / span as f32 * 2.0 - 1.0
+            }
+        }
+    }
+
+    /// Maps the value to `range_min..=range_max`, `range_max` can be less than `range_min`
+    /// for inverted output
     pub fn output(&self, range_min: u16, range_max: u16) -> u16 {
-        let scale = (range_max - range_min) as f32 / (self.max - self.min) as f32;
-        let result = range_min as f32 + (self.output_ranged() - self.min) as f32 * scale;
+        self.output_in(range_min, range_max)
+    }
 
-        result.floor() as u16
+    /// Maps the value to `-32767..=32767`, as used by HID descriptors
+    pub fn output_signed(&self) -> i16 {
+        self.output_in(-i16::MAX, i16::MAX)
+    }
+
+    /// Maps the value to `range_min..=range_max` range of any [Sample] type,
+    /// `range_max` can be less than `range_min` for inverted output
+    pub fn output_in<T: Sample>(&self, range_min: T, range_max: T) -> T {
+        let Some(center) = self.center else {
+            let span = self.max.to_i64() - self.min.to_i64();
+            if span <= 0 {
+                return range_min;
+            }
+            let scale = (range_max.to_i64() - range_min.to_i64()) as f32 / span as f32;
+            let offset = (self.output_ranged().to_i64() - self.min.to_i64()) as f32 * scale;
+            return T::from_f32(self.rounding.round(range_min.to_f32() + offset));
+        };
+
+        map_position(
+            self.centered_position(center),
+            range_min,
+            range_max,
+            self.rounding,
+        )
+    }
+
+    /// Same as [Axis::output], but uses only integer arithmetic
+    pub fn output_fixed(&self, range_min: u16, range_max: u16) -> u16 {
+        self.output_fixed_in(range_min, range_max)
+    }
+
+    /// Same as [Axis::output_in], but uses only integer arithmetic
+    pub fn output_fixed_in<T: Sample>(&self, range_min: T, range_max: T) -> T {
+        // `range_min + range * offset / span`
+        let (offset, span) = if let Some(center) = self.center {
+            let (numerator, denominator) = self.centered_ratio(center);
+            (denominator + numerator, 2 * denominator)
+        } else {
+            let span = self.max.to_i64() - self.min.to_i64();
+            if span <= 0 {
+                return range_min;
+            }
+            (self.output_ranged().to_i64() - self.min.to_i64(), span)
+        };
+
+        let range = range_max.to_i64() - range_min.to_i64();
+        // Products of 16-bit values fit `i64`, `i128` is only compiled in for 32-bit samples
+        let (quotient, remainder) = if size_of::<S>() <= 2 && size_of::<T>() <= 2 {
+            let product = offset * range;
+            (product.div_euclid(span), product.rem_euclid(span))
+        } else {
+            let (product, span) = (offset as i128 * range as i128, span as i128);
+            (
+                product.div_euclid(span) as i64,
+                product.rem_euclid(span) as i64,
+            )
+        };
+
+        let integer = range_min.to_i64() + quotient;
+        T::from_i64(self.rounding.round_ratio(integer, remainder, span))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::effects::Lerp;
+    use crate::effects::{
+        Curve, Deadzone, Expo, Lerp, Median, MovingAverage, OneEuro, Smooth, Spline,
+    };
+    use core::sync::atomic::{AtomicUsize, Ordering};
 
     #[test]
     fn basic_output() {
@@ -152,4 +801,726 @@ mod tests {
         axis.update(100, effects.iter_mut());
         assert_eq!(axis.output(0, 128), 93);
     }
+
+    #[test]
+    fn deadzone_effect() {
+        let mut axis: Axis = Axis::new(0, 200, false);
+        let mut effects = [Deadzone::new(0, 200, 10, 20).into()];
+
+        axis.update(95, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 100);
+        axis.update(110, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 100);
+        axis.update(15, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 0);
+        axis.update(190, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 200);
+        axis.update(55, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 50);
+        axis.update(145, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 150);
+    }
+
+    #[test]
+    fn deadzone_custom_center() {
+        let mut axis: Axis = Axis::new(0, 200, false);
+        let mut effects = [Deadzone::new(0, 200, 5, 0).with_center(50).into()];
+
+        axis.update(53, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 50);
+        axis.update(0, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 0);
+        axis.update(200, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 200);
+    }
+
+    #[test]
+    fn centered_output() {
+        let mut axis = Axis::new_centered(0, 40, 200, false);
+        axis.update(40, []);
+        assert_eq!(axis.output(0, 200), 100);
+        axis.update(20, []);
+        assert_eq!(axis.output(0, 200), 50);
+        axis.update(120, []);
+        assert_eq!(axis.output(0, 200), 150);
+        axis.update(0, []);
+        assert_eq!(axis.output(0, 200), 0);
+        axis.update(200, []);
+        assert_eq!(axis.output(0, 200), 200);
+    }
+
+    #[test]
+    fn centered_reversed() {
+        let mut axis = Axis::new_centered(0, 40, 200, true);
+        axis.update(40, []);
+        assert_eq!(axis.output(0, 200), 100);
+        axis.update(20, []);
+        assert_eq!(axis.output(0, 200), 150);
+        axis.update(200, []);
+        assert_eq!(axis.output(0, 200), 0);
+    }
+
+    #[test]
+    fn auto_calibration() {
+        let mut axis = Axis::new(0, 1000, false);
+        axis.enable_auto_calibration(3, 150);
+
+        axis.update(500, []);
+        axis.update(550, []);
+        axis.update(450, []);
+        assert_eq!((axis.min, axis.max), (0, 1000));
+
+        axis.update(400, []);
+        assert_eq!((axis.min, axis.max), (400, 550));
+        assert_eq!(axis.output(0, 150), 0);
+
+        axis.update(600, []);
+        assert_eq!(axis.output(0, 200), 200);
+
+        axis.auto_calibration.as_mut().unwrap().freeze();
+        axis.update(700, []);
+        assert_eq!((axis.min, axis.max), (400, 600));
+        assert_eq!(axis.auto_calibration.unwrap().learned(), Some((400, 600)));
+
+        // Manual edits of a frozen axis are kept
+        axis.max = 800;
+        axis.update(700, []);
+        assert_eq!((axis.min, axis.max), (400, 800));
+
+        // Persisted range is trusted right away
+        let mut axis = Axis::new(0, 1000, false);
+        axis.auto_calibration = Some(AutoCalibration::new(100, 150).with_learned(600, 400));
+        axis.update(500, []);
+        assert_eq!((axis.min, axis.max), (400, 600));
+        axis.update(650, []);
+        assert_eq!((axis.min, axis.max), (400, 650));
+
+        let narrow = AutoCalibration::new(100, 150).with_learned(400, 500);
+        assert_eq!(narrow.trusted_range(), None);
+    }
+
+    #[test]
+    fn expo_effect() {
+        let mut axis: Axis = Axis::new(0, 200, false);
+        let mut effects = [Expo::new(0, 200, 0.5).into()];
+
+        axis.update(150, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 131);
+        axis.update(50, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 69);
+        axis.update(200, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 200);
+        axis.update(100, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 100);
+
+        let mut effects = [Expo::new(0, 200, -0.5).into()];
+        axis.update(150, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 169);
+    }
+
+    #[test]
+    fn expo_asymmetric() {
+        let mut axis: Axis = Axis::new(0, 200, false);
+        let mut effects = [Expo::asymmetric(0, 200, 0.0, 1.0).into()];
+
+        axis.update(50, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 50);
+        axis.update(150, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 113);
+    }
+
+    #[test]
+    fn curve_effect() {
+        static CURVE: Curve<5> =
+            Curve::new([(10, 0), (50, 20), (100, 100), (150, 180), (190, 200)]);
+
+        let mut axis = Axis::new(0, 200, false);
+        let mut effects = [(&CURVE).into(), Curve::new([(0, 200), (200, 0)]).into()];
+
+        axis.update(0, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 200);
+        axis.update(30, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 190);
+        axis.update(75, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 140);
+        axis.update(170, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 10);
+        axis.update(200, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 0);
+    }
+
+    #[test]
+    fn spline_effect() {
+        static SPLINE: Spline<5> =
+            Spline::new([(0, 0), (50, 10), (100, 100), (150, 110), (200, 200)]);
+
+        let mut axis = Axis::new(0, 200, false);
+        let mut effects = [(&SPLINE).into()];
+
+        axis.update(50, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 10);
+        axis.update(100, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 100);
+
+        let mut previous = 0;
+        for input in 0..=200 {
+            let output = SPLINE.evaluate(input);
+            assert!(output >= previous);
+            previous = output;
+        }
+    }
+
+    #[test]
+    fn spline_flat_segment() {
+        let spline = Spline::new([(0, 0), (50, 100), (100, 100), (200, 200)]);
+        for input in 50..=100 {
+            assert_eq!(spline.evaluate(input), 100);
+        }
+    }
+
+    #[test]
+    fn moving_average_effect() {
+        let mut axis = Axis::new(0, 1000, false);
+        let mut chain = Chain::new((MovingAverage::<4>::new(),));
+
+        // Warm-up averages the samples seen so far
+        axis.update(400, &mut chain);
+        assert_eq!(axis.output(0, 1000), 400);
+        axis.update(200, &mut chain);
+        assert_eq!(axis.output(0, 1000), 300);
+        for value in [600, 800, 1000] {
+            axis.update(value, &mut chain);
+        }
+        assert_eq!(axis.output(0, 1000), 650);
+
+        let mut effects: [DynEffect; 1] = [MovingAverage::<5>::new().into()];
+        let mut axis = Axis::new(0, 1000, false);
+        for value in [0, 0, 400, 200, 600, 800, 1000] {
+            axis.update(value, effects.iter_mut());
+        }
+        assert_eq!(axis.output(0, 1000), 600);
+
+        effects[0].reset();
+        axis.update(100, effects.iter_mut());
+        assert_eq!(axis.output(0, 1000), 100);
+
+        let mut average = MovingAverage::<3, i16>::new();
+        assert_eq!(Effect::update(&mut average, -1), -1);
+        assert_eq!(Effect::update(&mut average, -2), -2);
+
+        let mut average = MovingAverage::<255, u32>::new();
+        for _ in 0..300 {
+            Effect::update(&mut average, u32::MAX);
+        }
+        assert_eq!(Effect::update(&mut average, u32::MAX), u32::MAX);
+    }
+
+    #[test]
+    fn median_effect() {
+        let mut axis = Axis::new(0, 1000, false);
+        let mut effects: [DynEffect; 1] = [Median::<3>::new().into()];
+        let mut outputs = [0; 8];
+        for (output, value) in outputs
+            .iter_mut()
+            .zip([100, 100, 900, 100, 100, 500, 500, 500])
+        {
+            axis.update(value, effects.iter_mut());
+            *output = axis.output(0, 1000);
+        }
+        // Spike is rejected, the edge is kept sharp
+        assert_eq!(outputs, [100, 100, 100, 100, 100, 100, 500, 500]);
+
+        let mut chain = Chain::new((Median::<5, i16>::new(),));
+        let mut axis = Axis::<i16>::new(-1000, 1000, false);
+        for value in [-300, 700, 0, -1000, 200, 900, 100] {
+            axis.update(value, &mut chain);
+        }
+        // Median of the last 5 samples: 0, -1000, 200, 900, 100
+        assert_eq!(axis.output_in(-1000i16, 1000), 100);
+
+        chain.reset();
+        axis.update(-600, &mut chain);
+        assert_eq!(axis.output_in(-1000i16, 1000), -600);
+
+        assert!(DynEffect::<u16>::try_new(Median::<7>::new()).is_some());
+    }
+
+    #[test]
+    fn one_euro_effect() {
+        let mut effects: [DynEffect<u16, 32>; 1] =
+            [OneEuro::new(0.001, 1.0, 0.01, 1.0).unwrap().into()];
+        let mut axis = Axis::new(0, 1000, false).with_effect_size::<32>();
+
+        // Jitter at rest is filtered out
+        let (mut low, mut high) = (1000, 0);
+        for i in 0..2000 {
+            axis.update(if i % 2 == 0 { 490 } else { 510 }, effects.iter_mut());
+            if i >= 1000 {
+                low = low.min(axis.output(0, 1000));
+                high = high.max(axis.output(0, 1000));
+            }
+        }
+        assert!(low >= 498 && high <= 502, "{low}..={high}");
+
+        // Fast motion is followed with little lag, unlike with fixed cutoff
+        let step = |filter: &mut OneEuro| {
+            for _ in 0..20 {
+                Effect::<u16>::update(filter, 500);
+            }
+            let mut output = 0;
+            for _ in 0..20 {
+                output = Effect::<u16>::update(filter, 900);
+            }
+            output
+        };
+        assert!(step(&mut OneEuro::new(0.001, 1.0, 0.01, 1.0).unwrap()) > 850);
+        assert!(step(&mut OneEuro::new(0.001, 1.0, 0.0, 1.0).unwrap()) < 600);
+        // Same number of samples covers more time at lower sample rate
+        assert!(step(&mut OneEuro::new(0.01, 1.0, 0.0, 1.0).unwrap()) > 700);
+
+        let mut filter = OneEuro::new(0.005, 1.0, 0.01, 1.0).unwrap();
+        let mut timed = filter.clone();
+        for value in [100u16, 400, 300, 800] {
+            assert_eq!(
+                Effect::update(&mut filter, value),
+                timed.filter(value, 0.005)
+            );
+        }
+
+        effects[0].set_param("sample_period", 0.01).unwrap();
+        assert_eq!(effects[0].param("sample_period"), Some(0.01));
+        assert_eq!(
+            effects[0].set_param("min_cutoff", 0.0),
+            Err(AxisError::InvalidFactor)
+        );
+        assert_eq!(
+            effects[0].set_param("gain", 1.0),
+            Err(AxisError::UnknownParam)
+        );
+        assert!(OneEuro::new(0.0, 1.0, 0.0, 1.0).is_err());
+        assert!(OneEuro::new(-0.001, 1.0, 0.0, 1.0).is_err());
+        assert_eq!(
+            OneEuro::new(0.001, 1.0, 0.0, 0.0).err(),
+            Some(AxisError::InvalidFactor)
+        );
+
+        // Duplicate timestamps don't break the filter
+        let mut filter = OneEuro::new(0.001, 1.0, 0.01, 2.0).unwrap();
+        assert_eq!(filter.filter(500u16, 0.001), 500);
+        assert_eq!(filter.filter(900u16, 0.0), 500);
+        assert_eq!(filter.filter(900u16, -0.001), 500);
+        assert!(filter.filter(900u16, 0.001) > 500);
+        for param in OneEuro::DESCRIPTOR.params {
+            assert!(effects[0].param(param.name).is_some());
+        }
+    }
+
+    #[test]
+    fn generic_samples() {
+        let mut axis = Axis::<u8>::new(0, 255, true);
+        let mut effects = [Lerp::new(0.5).into()];
+        axis.update(0, effects.iter_mut());
+        axis.update(200, effects.iter_mut());
+        assert_eq!(axis.output(0, 1000), 607);
+
+        let mut axis = Axis::<i16>::new_centered(-1000, 0, 1000, false);
+        let mut effects = [Deadzone::new(-1000, 1000, 100, 0).into()];
+        axis.update(-50, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 100);
+        axis.update(-550, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 50);
+
+        let mut axis = Axis::<u32>::new(0, 0xFF_FFFF, false);
+        let mut effects = [Curve::new([(0, 0), (0xFF_FFFF, 0x7F_FFFF)]).into()];
+        axis.update(0xFF_FFFF, effects.iter_mut());
+        assert_eq!(axis.output(0, 1000), 499);
+    }
+
+    #[test]
+    fn full_range_samples() {
+        let curve = Curve::new([(0u32, 0), (u32::MAX, u32::MAX)]);
+        assert_eq!(curve.evaluate(u32::MAX - 1), u32::MAX - 1);
+        assert_eq!(curve.evaluate(1 << 31), 1 << 31);
+        let curve = Curve::new([(i32::MIN, i32::MAX), (i32::MAX, i32::MIN)]);
+        assert_eq!(curve.evaluate(i32::MIN + 1), i32::MAX - 1);
+        assert_eq!(curve.evaluate(0), -1);
+
+        let mut deadzone = Deadzone::new(0u32, u32::MAX, 1000, 0);
+        assert_eq!(Effect::update(&mut deadzone, u32::MAX), u32::MAX);
+        assert_eq!(Effect::update(&mut deadzone, u32::MAX - 1), u32::MAX - 2);
+        assert_eq!(Effect::update(&mut deadzone, 1), 1);
+
+        let mut deadzone = Deadzone::new(i32::MIN, i32::MAX, 0, 1000);
+        assert_eq!(Effect::update(&mut deadzone, i32::MIN + 1000), i32::MIN);
+        assert_eq!(Effect::update(&mut deadzone, i32::MAX - 1001), i32::MAX - 2);
+        assert_eq!(Effect::update(&mut deadzone, -1), -1);
+    }
+
+    #[test]
+    fn signed_and_inverted_output() {
+        let mut axis: Axis = Axis::new(0, 200, false);
+        axis.update(50, []);
+        assert_eq!(axis.output(200, 0), 150);
+        assert_eq!(axis.output_signed(), -16384);
+        assert_eq!(axis.output_in(-100i16, 100), -50);
+        assert_eq!(axis.output_in(1000i32, -1000), 500);
+        assert_eq!(axis.output_normalized(), -0.5);
+        assert_eq!(axis.output_fixed(200, 0), 150);
+
+        let mut axis: Axis = Axis::new_centered(0, 40, 200, true);
+        axis.update(20, []);
+        assert_eq!(axis.output_normalized(), 0.5);
+        assert_eq!(axis.output(255, 0), 63);
+    }
+
+    #[test]
+    fn rounding_modes() {
+        let mut axis: Axis = Axis::new(0, 3, false);
+        axis.update(1, []);
+        assert_eq!(axis.output(0, 10), 3);
+        assert_eq!(axis.output_fixed(0, 10), 3);
+
+        axis.rounding = Rounding::Nearest;
+        assert_eq!(axis.output(0, 10), 3);
+        assert_eq!(axis.output(0, 11), 4);
+        assert_eq!(axis.output_fixed(0, 11), 4);
+
+        axis.rounding = Rounding::Ceil;
+        assert_eq!(axis.output(0, 10), 4);
+        assert_eq!(axis.output_fixed(0, 10), 4);
+        assert_eq!(axis.output_in(0i16, -10), -3);
+    }
+
+    #[test]
+    fn fixed_output_full_range() {
+        let mut axis = Axis::<u32>::new(0, u32::MAX, false);
+        axis.update(3 << 30, []);
+        assert_eq!(axis.output_fixed_in(0u32, u32::MAX), 3 << 30);
+        axis.update(u32::MAX, []);
+        assert_eq!(axis.output_fixed_in(0u32, u32::MAX), u32::MAX);
+        assert_eq!(axis.output_fixed_in(u32::MAX, 0), 0);
+
+        let mut axis = Axis::<i32>::new(i32::MIN, i32::MAX, false);
+        axis.update(0, []);
+        assert_eq!(axis.output_fixed_in(i32::MIN, i32::MAX), 0);
+        axis.update(i32::MAX, []);
+        assert_eq!(axis.output_fixed_in(i32::MIN, i32::MAX), i32::MAX);
+        assert_eq!(axis.output_fixed_in(i32::MAX, i32::MIN), i32::MIN);
+    }
+
+    #[test]
+    fn output_empty_range() {
+        let mut axis: Axis = Axis::new(50, 50, false);
+        axis.update(50, []);
+        assert_eq!(axis.output_fixed(0, 1000), 0);
+        assert_eq!(axis.output_fixed(100, 1000), 100);
+        assert_eq!(axis.output(100, 1000), 100);
+        assert_eq!(axis.output(1000, 100), 1000);
+        assert_eq!(axis.output_in(-10i16, 10), -10);
+        assert_eq!(axis.output_normalized(), -1.0);
+
+        // Auto-calibration without minimal travel learns empty range after the first sample
+        let mut axis: Axis = Axis::new(0, 1000, false);
+        axis.enable_auto_calibration(1, 0);
+        axis.update(50, []);
+        assert_eq!(axis.output_fixed(0, 1000), 0);
+        assert_eq!(axis.output(0, 1000), 0);
+        assert_eq!(axis.output_normalized(), -1.0);
+    }
+
+    #[test]
+    fn builder_validation() {
+        assert_eq!(
+            Axis::builder(10u16, 10).build().err(),
+            Some(AxisError::EmptyRange)
+        );
+        assert_eq!(
+            Axis::builder(20u16, 10).build().err(),
+            Some(AxisError::InvertedBounds)
+        );
+        assert_eq!(
+            Axis::builder(0u16, 10).center(11).build().err(),
+            Some(AxisError::CenterOutOfRange)
+        );
+        assert_eq!(
+            Axis::builder(-10i16, 10)
+                .step_filter_factor(-1)
+                .build()
+                .err(),
+            Some(AxisError::InvalidFactor)
+        );
+
+        let mut axis = Axis::builder(0u16, 200)
+            .center(40)
+            .reversed(true)
+            .step_filter_factor(2)
+            .rounding(Rounding::Nearest)
+            .build()
+            .unwrap();
+        axis.update(20, []);
+        assert_eq!(axis.output(0, 200), 150);
+    }
+
+    #[test]
+    fn effect_validation() {
+        assert_eq!(Lerp::try_new(1.5).err(), Some(AxisError::InvalidFactor));
+        assert_eq!(
+            Lerp::try_new(f32::NAN).err(),
+            Some(AxisError::InvalidFactor)
+        );
+        assert_eq!(
+            Expo::<u16>::try_new(0, 10, -2.0).err(),
+            Some(AxisError::InvalidFactor)
+        );
+        assert_eq!(
+            Deadzone::<u16>::try_new(10, 0, 1, 1).err(),
+            Some(AxisError::InvertedBounds)
+        );
+        assert_eq!(
+            Smooth::<u16>::try_new(0).err(),
+            Some(AxisError::InvalidFactor)
+        );
+    }
+
+    #[test]
+    fn effect_reset_and_bypass() {
+        let mut effects: [DynEffect; 2] = [Lerp::new(0.5).into(), Smooth::new(1000).into()];
+        let mut axis = Axis::new(0, 1000, false);
+        axis.update(1000, effects.iter_mut());
+        axis.update(0, effects.iter_mut());
+        assert_eq!(axis.output(0, 1000), 500);
+
+        effects.iter_mut().for_each(|effect| effect.reset());
+        axis.update(0, effects.iter_mut());
+        assert_eq!(axis.output(0, 1000), 0);
+
+        effects[0].set_enabled(false);
+        assert!(!effects[0].is_enabled());
+        axis.update(800, effects.iter_mut());
+        assert_eq!(axis.output(0, 1000), 800);
+        effects[0].set_enabled(true);
+        axis.update(1000, effects.iter_mut());
+        assert_eq!(axis.output(0, 1000), 500);
+    }
+
+    #[test]
+    fn effect_params() {
+        let mut lerp: DynEffect = Lerp::new(0.5).into();
+        assert_eq!(lerp.param("factor"), Some(0.5));
+        assert_eq!(lerp.param("speed"), None);
+        lerp.set_param("factor", 0.25).unwrap();
+        assert_eq!(lerp.param("factor"), Some(0.25));
+        assert_eq!(lerp.set_param("factor", 2.0), Err(AxisError::InvalidFactor));
+        assert_eq!(lerp.set_param("speed", 1.0), Err(AxisError::UnknownParam));
+
+        let mut deadzone: DynEffect = Deadzone::new(0, 1000, 10, 0).into();
+        deadzone.set_param("inner", 50.0).unwrap();
+        deadzone.set_param("center", 600.0).unwrap();
+        assert_eq!(deadzone.update(640), 600);
+        deadzone.set_param("max", 500.0).unwrap();
+        assert_eq!(deadzone.param("center"), Some(500.0));
+        assert_eq!(deadzone.set_param("min", 500.0), Err(AxisError::EmptyRange));
+        assert_eq!(
+            deadzone.set_param("outer", -1.0),
+            Err(AxisError::InvalidFactor)
+        );
+
+        let mut expo: DynEffect = Expo::new(0, 1000, 0.2).into();
+        expo.set_param("high", 0.6).unwrap();
+        assert_eq!(expo.param("low"), Some(0.2));
+        assert_eq!(expo.param("factor"), Some(0.4));
+        expo.set_param("factor", 0.0).unwrap();
+        assert_eq!(expo.update(750), 750);
+        assert_eq!(expo.set_param("low", 1.5), Err(AxisError::InvalidFactor));
+    }
+
+    #[test]
+    fn sample_params() {
+        // `f32` parameters round 100_000_001 to 100_000_000
+        let mut deadzone: DynEffect<i32, 24> = Deadzone::new(0, 1000, 0, 0).into();
+        deadzone.set_param_sample("max", 100_000_001).unwrap();
+        assert_eq!(deadzone.param_sample("max"), Some(100_000_001));
+        assert_eq!(deadzone.param("max"), Some(100_000_000.0));
+        assert_eq!(deadzone.update(100_000_001), 100_000_001);
+        assert_eq!(
+            deadzone.set_param_sample("inner", -1),
+            Err(AxisError::InvalidFactor)
+        );
+
+        let mut expo: DynEffect<u32, 24> = Expo::new(0, 1000, 0.5).into();
+        assert_eq!(expo.param_sample("factor"), None);
+        assert_eq!(
+            expo.set_param_sample("factor", 1),
+            Err(AxisError::UnknownParam)
+        );
+        expo.set_param_sample("center", 16_777_217).unwrap();
+        assert_eq!(expo.param_sample("center"), Some(1000));
+
+        let mut smooth = Bypass::new(Smooth::new(1u32));
+        smooth.set_param_sample("speed", 16_777_217).unwrap();
+        assert_eq!(smooth.param_sample("speed"), Some(16_777_217));
+
+        // Default implementation goes through `f32` parameters of sample units only
+        let lerp: DynEffect = Lerp::new(0.5).into();
+        assert_eq!(lerp.param_sample("factor"), None);
+        let mut fixed_expo: DynEffect<u32, 32> =
+            fixed::Expo::new(0, 1000, Fixed::from_ratio(1, 2)).into();
+        fixed_expo.set_param_sample("max", 2000).unwrap();
+        assert_eq!(fixed_expo.update(2000), 2000);
+    }
+
+    #[test]
+    fn effect_descriptors() {
+        static CURVE: Curve<2> = Curve::new([(0, 0), (1000, 1000)]);
+
+        let effects: [DynEffect; 5] = [
+            Lerp::new(0.5).into(),
+            Smooth::new(10).into(),
+            Deadzone::new(0, 1000, 10, 0).into(),
+            Expo::new(0, 1000, 0.3).into(),
+            (&CURVE).into(),
+        ];
+        let names = effects
+            .each_ref()
+            .map(|effect| effect.descriptor().unwrap().name);
+        assert_eq!(names, ["lerp", "smooth", "deadzone", "expo", "curve"]);
+
+        for effect in &effects {
+            for param in effect.descriptor().unwrap().params {
+                assert!(effect.param(param.name).is_some(), "{}", param.name);
+                assert!((param.min..=param.max).contains(&param.default));
+            }
+        }
+
+        let deadzone = effects[2].descriptor().unwrap();
+        assert_eq!(deadzone.param("center").unwrap().default, 32767.0);
+        assert_eq!(deadzone.param("inner").unwrap().unit, ParamUnit::Distance);
+        assert_eq!(deadzone.param("min").unwrap().unit, ParamUnit::Sample);
+        assert!(deadzone.param("factor").is_none());
+
+        let signed = Deadzone::<i16>::default();
+        let center = Effect::descriptor(&signed)
+            .unwrap()
+            .param("center")
+            .unwrap();
+        assert_eq!((center.min, center.default), (-32768.0, -1.0));
+
+        struct Custom;
+        impl Effect for Custom {
+            fn update(&mut self, input: u16) -> u16 {
+                input
+            }
+        }
+        assert!(Custom.descriptor().is_none());
+    }
+
+    // Tests below exercise unsafe storage of DynEffect, run them with `cargo +nightly miri test`
+
+    #[test]
+    fn dyn_effect_drops_effect() {
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(Clone)]
+        struct Tracked;
+
+        impl Effect for Tracked {
+            fn update(&mut self, input: u16) -> u16 {
+                input
+            }
+        }
+
+        impl Drop for Tracked {
+            fn drop(&mut self) {
+                DROPS.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let effect: DynEffect = DynEffect::new(Tracked);
+        let clone = effect.clone();
+        drop(effect);
+        assert_eq!(DROPS.load(Ordering::Relaxed), 1);
+        drop(clone);
+        assert_eq!(DROPS.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn dyn_effect_clones_effect() {
+        static CLONES: AtomicUsize = AtomicUsize::new(0);
+
+        struct Counter(u16);
+
+        impl Clone for Counter {
+            fn clone(&self) -> Self {
+                CLONES.fetch_add(1, Ordering::Relaxed);
+                Self(self.0)
+            }
+        }
+
+        impl Effect for Counter {
+            fn update(&mut self, input: u16) -> u16 {
+                self.0 += 1;
+                input + self.0
+            }
+        }
+
+        let mut effect: DynEffect = DynEffect::new(Counter(0));
+        assert_eq!(effect.update(10), 11);
+        let mut clone = effect.clone();
+        assert_eq!(CLONES.load(Ordering::Relaxed), 1);
+        assert_eq!(effect.update(10), 12);
+        assert_eq!(clone.update(10), 12);
+        assert_eq!(clone.update(10), 13);
+    }
+
+    #[test]
+    fn dyn_effect_aligned_storage() {
+        #[derive(Clone)]
+        struct Wide(u64, f64);
+
+        impl Effect for Wide {
+            fn update(&mut self, input: u16) -> u16 {
+                assert_eq!(
+                    self as *const Self as usize % core::mem::align_of::<Self>(),
+                    0
+                );
+                self.0 += input as u64;
+                self.1 += 0.5;
+                (self.0 + self.1 as u64) as u16
+            }
+        }
+
+        let mut effects = [DynEffect::new(Wide(0, 0.0)), Lerp::new(0.5).into()];
+        let mut axis: Axis = Axis::new(0, 1000, false);
+        axis.update(10, effects.iter_mut());
+        axis.update(10, effects.iter_mut());
+        assert_eq!(axis.output(0, 1000), 15);
+    }
+
+    #[test]
+    fn dyn_effect_custom_size() {
+        let mut effects: [DynEffect<u32, 24>; 2] = [
+            Deadzone::new(0u32, 200, 10, 0).into(),
+            Smooth::new(1000).into(),
+        ];
+        let mut axis = Axis::new(0u32, 200, false).with_effect_size::<24>();
+        axis.update(95, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 100);
+        axis.update(200, effects.iter_mut());
+        assert_eq!(axis.output(0, 200), 200);
+
+        let deadzone = Deadzone::new(0u32, 200, 10, 0);
+        assert!(DynEffect::<u32>::try_new(deadzone.clone()).is_none());
+        assert!(DynEffect::<u32, 24>::try_new(deadzone).is_some());
+
+        let mut small: [DynEffect<u16, 8>; 1] = [Smooth::new(10).into()];
+        assert!(core::mem::size_of::<DynEffect<u16, 8>>() < core::mem::size_of::<DynEffect>());
+        // Storage, one vtable pointer and the enabled flag
+        assert_eq!(
+            core::mem::size_of::<DynEffect>(),
+            MAX_EFFECT_SIZE + 2 * core::mem::size_of::<usize>()
+        );
+        let mut axis = Axis::new(0, 100, false).with_effect_size::<8>();
+        axis.update(50, small.iter_mut());
+        assert_eq!(axis.output(0, 100), 10);
+    }
 }
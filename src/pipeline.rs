@@ -0,0 +1,262 @@
+use crate::{Axis, AxisError, DynEffect, MAX_EFFECT_SIZE, Sample};
+use core::ops::{Deref, DerefMut};
+
+/// Fixed-capacity effect chain of up to `CAP` [DynEffect]s with `N` bytes storage each.
+/// `&mut Pipeline` can be passed to [Axis::update] as the chain.
+#[derive(Clone)]
+pub struct Pipeline<S: Sample = u16, const CAP: usize = 8, const N: usize = MAX_EFFECT_SIZE> {
+    slots: [Option<DynEffect<S, N>>; CAP],
+    len: usize,
+}
+
+impl<S: Sample, const CAP: usize, const N: usize> Default for Pipeline<S, CAP, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Sample, const CAP: usize, const N: usize> Pipeline<S, CAP, N> {
+    pub fn new() -> Self {
+        Self {
+            slots: [const { None }; CAP],
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        CAP
+    }
+
+    /// Appends `effect` to the end of the chain
+    pub fn push(&mut self, effect: impl Into<DynEffect<S, N>>) -> Result<(), AxisError> {
+        self.insert(self.len, effect)
+    }
+
+    /// Inserts `effect` at `index`, shifting following effects towards the end
+    pub fn insert(
+        &mut self,
+        index: usize,
+        effect: impl Into<DynEffect<S, N>>,
+    ) -> Result<(), AxisError> {
+        if index > self.len {
+            return Err(AxisError::IndexOutOfBounds);
+        }
+        if self.len == CAP {
+            return Err(AxisError::PipelineFull);
+        }
+
+        self.slots[self.len] = Some(effect.into());
+        self.slots[index..=self.len].rotate_right(1);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Removes and returns the effect at `index`, shifting following effects towards the start
+    pub fn remove(&mut self, index: usize) -> Result<DynEffect<S, N>, AxisError> {
+        if index >= self.len {
+            return Err(AxisError::IndexOutOfBounds);
+        }
+
+        self.slots[index..self.len].rotate_left(1);
+        self.len -= 1;
+        self.slots[self.len]
+            .take()
+            .ok_or(AxisError::IndexOutOfBounds)
+    }
+
+    /// Replaces the effect at `index`, returns the old effect
+    pub fn replace(
+        &mut self,
+        index: usize,
+        effect: impl Into<DynEffect<S, N>>,
+    ) -> Result<DynEffect<S, N>, AxisError> {
+        self.slots[..self.len]
+            .get_mut(index)
+            .and_then(|slot| slot.replace(effect.into()))
+            .ok_or(AxisError::IndexOutOfBounds)
+    }
+
+    pub fn get(&self, index: usize) -> Option<&DynEffect<S, N>> {
+        self.slots[..self.len].get(index)?.as_ref()
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut DynEffect<S, N>> {
+        self.slots[..self.len].get_mut(index)?.as_mut()
+    }
+
+    /// Resets all effects, see [crate::Effect::reset]
+    pub fn reset(&mut self) {
+        self.iter_mut().for_each(|effect| effect.reset());
+    }
+
+    /// Removes all effects
+    pub fn clear(&mut self) {
+        self.slots.iter_mut().for_each(|slot| *slot = None);
+        self.len = 0;
+    }
+
+    pub fn iter(&self) -> core::iter::Flatten<core::slice::Iter<'_, Option<DynEffect<S, N>>>> {
+        self.slots[..self.len].iter().flatten()
+    }
+
+    pub fn iter_mut(
+        &mut self,
+    ) -> core::iter::Flatten<core::slice::IterMut<'_, Option<DynEffect<S, N>>>> {
+        self.slots[..self.len].iter_mut().flatten()
+    }
+}
+
+impl<'a, S: Sample, const CAP: usize, const N: usize> IntoIterator for &'a Pipeline<S, CAP, N> {
+    type Item = &'a DynEffect<S, N>;
+    type IntoIter = core::iter::Flatten<core::slice::Iter<'a, Option<DynEffect<S, N>>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, S: Sample, const CAP: usize, const N: usize> IntoIterator for &'a mut Pipeline<S, CAP, N> {
+    type Item = &'a mut DynEffect<S, N>;
+    type IntoIter = core::iter::Flatten<core::slice::IterMut<'a, Option<DynEffect<S, N>>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// [Axis] that owns its effect [Pipeline], so the chain doesn't have to be passed on every
+/// update. Dereferences to the inner [Axis] for configuration and output.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PipelineAxis<S: Sample = u16, const CAP: usize = 8, const N: usize = MAX_EFFECT_SIZE> {
+    axis: Axis<S, N>,
+    pipeline: Pipeline<S, CAP, N>,
+}
+
+impl<S: Sample, const CAP: usize, const N: usize> PipelineAxis<S, CAP, N> {
+    pub fn new(axis: Axis<S, N>) -> Self {
+        Self {
+            axis,
+            pipeline: Pipeline::new(),
+        }
+    }
+
+    /// Processes `value` through the owned pipeline
+    pub fn update(&mut self, value: S) {
+        self.axis.update(value, &mut self.pipeline);
+    }
+
+    pub fn pipeline(&self) -> &Pipeline<S, CAP, N> {
+        &self.pipeline
+    }
+
+    pub fn pipeline_mut(&mut self) -> &mut Pipeline<S, CAP, N> {
+        &mut self.pipeline
+    }
+
+    /// See [Pipeline::push]
+    pub fn push(&mut self, effect: impl Into<DynEffect<S, N>>) -> Result<(), AxisError> {
+        self.pipeline.push(effect)
+    }
+
+    /// See [Pipeline::insert]
+    pub fn insert(
+        &mut self,
+        index: usize,
+        effect: impl Into<DynEffect<S, N>>,
+    ) -> Result<(), AxisError> {
+        self.pipeline.insert(index, effect)
+    }
+
+    /// See [Pipeline::remove]
+    pub fn remove(&mut self, index: usize) -> Result<DynEffect<S, N>, AxisError> {
+        self.pipeline.remove(index)
+    }
+
+    /// See [Pipeline::replace]
+    pub fn replace(
+        &mut self,
+        index: usize,
+        effect: impl Into<DynEffect<S, N>>,
+    ) -> Result<DynEffect<S, N>, AxisError> {
+        self.pipeline.replace(index, effect)
+    }
+}
+
+impl<S: Sample, const CAP: usize, const N: usize> Deref for PipelineAxis<S, CAP, N> {
+    type Target = Axis<S, N>;
+
+    fn deref(&self) -> &Axis<S, N> {
+        &self.axis
+    }
+}
+
+impl<S: Sample, const CAP: usize, const N: usize> DerefMut for PipelineAxis<S, CAP, N> {
+    fn deref_mut(&mut self) -> &mut Axis<S, N> {
+        &mut self.axis
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::effects::{Curve, Deadzone, Smooth};
+
+    #[test]
+    fn pipeline_editing() {
+        let mut pipeline: Pipeline<u16, 3> = Pipeline::new();
+        let invert = Curve::new([(0, 200), (200, 0)]);
+        pipeline.push(invert).unwrap();
+        pipeline.push(Smooth::new(50)).unwrap();
+        pipeline.insert(0, Deadzone::new(0, 200, 10, 0)).unwrap();
+        assert_eq!(pipeline.push(invert), Err(AxisError::PipelineFull));
+        assert_eq!(pipeline.len(), 3);
+
+        let mut axis = Axis::new(0, 200, false);
+        axis.update(105, &mut pipeline);
+        assert_eq!(axis.output(0, 200), 50);
+
+        let mut smooth = pipeline.remove(2).unwrap();
+        assert_eq!(smooth.update(200), 100);
+        assert!(pipeline.remove(2).is_err());
+        pipeline
+            .replace(1, Curve::new([(0, 0), (200, 100)]))
+            .unwrap();
+        assert_eq!(pipeline.insert(3, invert), Err(AxisError::IndexOutOfBounds));
+        assert!(pipeline.replace(2, invert).is_err());
+
+        axis.update(150, &mut pipeline);
+        assert_eq!(axis.output(0, 200), 72);
+        pipeline.clear();
+        assert!(pipeline.is_empty());
+        axis.update(150, &mut pipeline);
+        assert_eq!(axis.output(0, 200), 150);
+    }
+
+    #[test]
+    fn pipeline_axis() {
+        let mut axis: PipelineAxis = PipelineAxis::new(Axis::new(0, 1000, false));
+        axis.push(Deadzone::new(0, 1000, 20, 0)).unwrap();
+        axis.step_filter_factor = 5;
+
+        axis.update(510);
+        assert_eq!(axis.output(0, 1000), 500);
+        axis.update(512);
+        assert_eq!(axis.output(0, 1000), 500);
+        axis.update(700);
+        assert_eq!(axis.output(0, 1000), 687);
+
+        axis.replace(0, Curve::new([(0, 1000), (1000, 0)])).unwrap();
+        axis.update(700);
+        assert_eq!(axis.output(0, 1000), 300);
+        assert!(axis.remove(0).is_ok());
+        assert!(axis.pipeline().is_empty());
+    }
+}
@@ -0,0 +1,447 @@
+//! Registry of effects with stable numeric IDs, turns [DynEffect]s into bytes and back.
+//!
+//! Each effect is stored as a record (little-endian): ID, flags, parameter count and
+//! parameters as `f32` in [crate::EffectDescriptor] order. A chain is a sequence of records.
+//!
+//! Records only carry descriptor parameters, so effects configured by anything else can't be
+//! registered. Notably [crate::effects::Curve] and the splines have no built-in IDs, as their
+//! points don't fit the record format, and neither do window sizes of
+//! [crate::effects::MovingAverage] and [crate::effects::Median]. Such effects can be stored with
+//! the `serde` feature instead, or registered as custom entries creating them with
+//! fixed configuration, e.g. a curve from a static table.
+
+use crate::{
+    AxisError, DynEffect, Effect, EffectDescriptor, Fixed, MAX_EFFECT_SIZE, Pipeline, Sample,
+    effects::{Deadzone, Expo, Lerp, OneEuro, Smooth},
+    fits, fixed,
+};
+use core::fmt;
+
+pub const LERP_ID: u8 = 1;
+pub const SMOOTH_ID: u8 = 2;
+pub const DEADZONE_ID: u8 = 3;
+pub const EXPO_ID: u8 = 4;
+pub const FIXED_LERP_ID: u8 = 5;
+pub const FIXED_EXPO_ID: u8 = 6;
+pub const ONE_EURO_ID: u8 = 7;
+/// First ID available for custom effects, lower IDs are reserved for built-in ones
+pub const CUSTOM_ID: u8 = 128;
+
+/// Size of effect record without parameters
+pub const RECORD_HEADER_SIZE: usize = 3;
+
+const FLAG_ENABLED: u8 = 1 << 0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegistryError {
+    /// No effect is registered with this ID
+    UnknownId(u8),
+    /// Effect has no descriptor or its descriptor name is not registered
+    Unregistered,
+    /// Data ends in the middle of a record
+    UnexpectedEnd,
+    /// Record parameter count does not match the effect descriptor
+    ParamCount,
+    /// Output buffer is too small
+    BufferTooSmall,
+    /// Effect or pipeline rejected the data
+    Rejected(AxisError),
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::UnknownId(id) => write!(f, "no effect is registered with ID {id}"),
+            RegistryError::Unregistered => write!(f, "effect is not registered"),
+            RegistryError::UnexpectedEnd => write!(f, "unexpected end of effect record"),
+            RegistryError::ParamCount => write!(f, "parameter count does not match effect"),
+            RegistryError::BufferTooSmall => write!(f, "buffer is too small"),
+            RegistryError::Rejected(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl core::error::Error for RegistryError {}
+
+/// Registered effect: stable ID, descriptor and constructor of the effect with
+/// default parameters
+pub struct EffectEntry<S: Sample = u16, const N: usize = MAX_EFFECT_SIZE> {
+    pub id: u8,
+    pub descriptor: &'static EffectDescriptor,
+    pub create: fn() -> DynEffect<S, N>,
+}
+
+impl<S: Sample, const N: usize> Clone for EffectEntry<S, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S: Sample, const N: usize> Copy for EffectEntry<S, N> {}
+
+impl<S: Sample, const N: usize> EffectEntry<S, N> {
+    pub const fn new(
+        id: u8,
+        descriptor: &'static EffectDescriptor,
+        create: fn() -> DynEffect<S, N>,
+    ) -> Self {
+        Self {
+            id,
+            descriptor,
+            create,
+        }
+    }
+}
+
+/// Entry of built-in effect `T` if it fits in `N` bytes
+const fn builtin<T, S: Sample, const N: usize>(
+    id: u8,
+    descriptor: &'static EffectDescriptor,
+    create: fn() -> DynEffect<S, N>,
+) -> Option<EffectEntry<S, N>> {
+    if fits::<T, N>() {
+        Some(EffectEntry::new(id, descriptor, create))
+    } else {
+        None
+    }
+}
+
+/// Constructor of built-in effect, only called for entries returned by [builtin]
+fn create<T: Effect<S> + Clone + Send + Sync + 'static, S: Sample, const N: usize>(
+    effect: T,
+) -> DynEffect<S, N> {
+    DynEffect::try_new(effect).expect("built-in effect fits in the storage")
+}
+
+/// Built-in effects extended with custom entries. Custom entries should use IDs starting at
+/// [CUSTOM_ID], built-in entries take precedence on conflicts.
+///
+/// Built-in effects that don't fit in `N` bytes are left out. With the default storage size all
+/// of them except `fixed_expo` (24 bytes) and `one_euro` (28 bytes) fit for 8 and 16-bit
+/// samples. With `u32` or `i32` samples `deadzone` and `expo` take 20 bytes and `fixed_expo` 28,
+/// so all built-in effects need e.g. `Registry<u32, 32>`
+#[derive(Clone, Copy)]
+pub struct Registry<'a, S: Sample = u16, const N: usize = MAX_EFFECT_SIZE> {
+    custom: &'a [EffectEntry<S, N>],
+}
+
+impl<S: Sample, const N: usize> Default for Registry<'_, S, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, S: Sample, const N: usize> Registry<'a, S, N> {
+    /// Registry of built-in effects
+    pub const fn new() -> Self {
+        Self { custom: &[] }
+    }
+
+    /// Registry of built-in and `custom` effects
+    pub const fn with_custom(custom: &'a [EffectEntry<S, N>]) -> Self {
+        Self { custom }
+    }
+
+    /// Built-in entries, `None` for effects that don't fit in `N` bytes
+    fn builtin() -> &'static [Option<EffectEntry<S, N>>] {
+        const {
+            &[
+                builtin::<Lerp, S, N>(LERP_ID, &Lerp::DESCRIPTOR, || create(Lerp::new(0.5))),
+                builtin::<Smooth<S>, S, N>(SMOOTH_ID, &Smooth::<S>::DESCRIPTOR, || {
+                    create(Smooth::new(S::from_i64(1)))
+                }),
+                builtin::<Deadzone<S>, S, N>(DEADZONE_ID, &Deadzone::<S>::DESCRIPTOR, || {
+                    create(Deadzone::new(S::MIN, S::MAX, S::default(), S::default()))
+                }),
+                builtin::<Expo<S>, S, N>(EXPO_ID, &Expo::<S>::DESCRIPTOR, || {
+                    create(Expo::new(S::MIN, S::MAX, 0.0))
+                }),
+                builtin::<fixed::Lerp, S, N>(FIXED_LERP_ID, &fixed::Lerp::DESCRIPTOR, || {
+                    create(fixed::Lerp::new(Fixed::from_ratio(1, 2)))
+                }),
+                builtin::<fixed::Expo<S>, S, N>(
+                    FIXED_EXPO_ID,
+                    &fixed::Expo::<S>::DESCRIPTOR,
+                    || create(fixed::Expo::new(S::MIN, S::MAX, Fixed::ZERO)),
+                ),
+                builtin::<OneEuro, S, N>(ONE_EURO_ID, &OneEuro::DESCRIPTOR, || {
+                    create(OneEuro::default())
+                }),
+            ]
+        }
+    }
+
+    /// Built-in entries followed by custom ones
+    pub fn entries(&self) -> impl Iterator<Item = &EffectEntry<S, N>> {
+        Self::builtin().iter().flatten().chain(self.custom)
+    }
+
+    pub fn by_id(&self, id: u8) -> Option<&EffectEntry<S, N>> {
+        self.entries().find(|entry| entry.id == id)
+    }
+
+    /// Finds entry by [EffectDescriptor] name
+    pub fn by_name(&self, name: &str) -> Option<&EffectEntry<S, N>> {
+        self.entries().find(|entry| entry.descriptor.name == name)
+    }
+
+    /// Writes `effect` record to `buf`, returns number of bytes written
+    pub fn encode(&self, effect: &DynEffect<S, N>, buf: &mut [u8]) -> Result<usize, RegistryError> {
+        let descriptor = effect.descriptor().ok_or(RegistryError::Unregistered)?;
+        let entry = self
+            .by_name(descriptor.name)
+            .ok_or(RegistryError::Unregistered)?;
+
+        let params = entry.descriptor.params;
+        let size = RECORD_HEADER_SIZE + params.len() * 4;
+        let buf = buf.get_mut(..size).ok_or(RegistryError::BufferTooSmall)?;
+
+        buf[0] = entry.id;
+        buf[1] = if effect.is_enabled() { FLAG_ENABLED } else { 0 };
+        buf[2] = params.len() as u8;
+        for (i, param) in params.iter().enumerate() {
+            let value = effect.param(param.name).unwrap_or(param.default);
+            let offset = RECORD_HEADER_SIZE + i * 4;
+            buf[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        }
+        Ok(size)
+    }
+
+    /// Writes records of all `effects` to `buf`, returns number of bytes written
+    pub fn encode_chain<'e>(
+        &self,
+        effects: impl IntoIterator<Item = &'e DynEffect<S, N>>,
+        buf: &mut [u8],
+    ) -> Result<usize, RegistryError> {
+        let mut size = 0;
+        for effect in effects {
+            size += self.encode(effect, &mut buf[size..])?;
+        }
+        Ok(size)
+    }
+
+    /// Reads one effect record, returns the effect and number of bytes read
+    pub fn decode(&self, bytes: &[u8]) -> Result<(DynEffect<S, N>, usize), RegistryError> {
+        let [id, flags, count, ..] = *bytes else {
+            return Err(RegistryError::UnexpectedEnd);
+        };
+        let entry = self.by_id(id).ok_or(RegistryError::UnknownId(id))?;
+
+        let params = entry.descriptor.params;
+        if count as usize != params.len() {
+            return Err(RegistryError::ParamCount);
+        }
+
+        let size = RECORD_HEADER_SIZE + params.len() * 4;
+        let data = bytes.get(..size).ok_or(RegistryError::UnexpectedEnd)?;
+
+        let mut effect = (entry.create)();
+        for (i, param) in params.iter().enumerate() {
+            let offset = RECORD_HEADER_SIZE + i * 4;
+            let value = f32::from_le_bytes([
+                data[offset],
+                data[offset + 1],
+                data[offset + 2],
+                data[offset + 3],
+            ]);
+            effect
+                .set_param(param.name, value)
+                .map_err(RegistryError::Rejected)?;
+        }
+        effect.set_enabled(flags & FLAG_ENABLED != 0);
+        Ok((effect, size))
+    }
+
+    /// Reads all records of `bytes` and appends the effects to `pipeline`
+    pub fn decode_into<const CAP: usize>(
+        &self,
+        mut bytes: &[u8],
+        pipeline: &mut Pipeline<S, CAP, N>,
+    ) -> Result<(), RegistryError> {
+        while !bytes.is_empty() {
+            let (effect, size) = self.decode(bytes)?;
+            pipeline.push(effect).map_err(RegistryError::Rejected)?;
+            bytes = &bytes[size..];
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Axis, Effect, ParamDescriptor, ParamUnit, effects::Curve};
+
+    #[derive(Clone)]
+    struct Offset(i32);
+
+    impl Offset {
+        const DESCRIPTOR: EffectDescriptor = EffectDescriptor {
+            name: "offset",
+            params: &[ParamDescriptor::new(
+                "offset",
+                -1000.0,
+                1000.0,
+                0.0,
+                ParamUnit::Sample,
+            )],
+        };
+    }
+
+    impl Effect for Offset {
+        fn update(&mut self, input: u16) -> u16 {
+            input.saturating_add_signed(self.0 as i16)
+        }
+
+        fn param(&self, name: &str) -> Option<f32> {
+            (name == "offset").then_some(self.0 as f32)
+        }
+
+        fn set_param(&mut self, name: &str, value: f32) -> Result<(), AxisError> {
+            match name {
+                "offset" => {
+                    self.0 = value as i32;
+                    Ok(())
+                }
+                _ => Err(AxisError::UnknownParam),
+            }
+        }
+
+        fn descriptor(&self) -> Option<&'static EffectDescriptor> {
+            Some(&Self::DESCRIPTOR)
+        }
+    }
+
+    static CUSTOM: [EffectEntry; 1] = [EffectEntry::new(CUSTOM_ID, &Offset::DESCRIPTOR, || {
+        DynEffect::new(Offset(0))
+    })];
+
+    #[test]
+    fn chain_roundtrip() {
+        let registry = Registry::with_custom(&CUSTOM);
+        let mut effects: [DynEffect; 4] = [
+            Deadzone::new(0, 1000, 20, 5).with_center(400).into(),
+            Expo::asymmetric(0, 1000, 0.2, -0.4).into(),
+            Lerp::new(0.25).into(),
+            DynEffect::new(Offset(-30)),
+        ];
+        effects[2].set_enabled(false);
+
+        let mut buf = [0; 128];
+        let size = registry.encode_chain(&effects, &mut buf).unwrap();
+        assert_eq!(size, 4 * RECORD_HEADER_SIZE + (5 + 6 + 1 + 1) * 4);
+
+        let mut pipeline: Pipeline = Pipeline::new();
+        registry.decode_into(&buf[..size], &mut pipeline).unwrap();
+        assert_eq!(pipeline.len(), 4);
+        assert!(!pipeline.get(2).unwrap().is_enabled());
+
+        let mut original = Axis::new(0, 1000, false);
+        let mut decoded = Axis::new(0, 1000, false);
+        for value in (0..=1000).step_by(50) {
+            original.update(value, effects.iter_mut());
+            decoded.update(value, &mut pipeline);
+            assert_eq!(original.output(0, 1000), decoded.output(0, 1000));
+        }
+    }
+
+    #[test]
+    fn one_euro_record() {
+        let registry: Registry<u16, 32> = Registry::new();
+        let filter: DynEffect<u16, 32> = OneEuro::new(0.005, 2.0, 0.01, 1.5).unwrap().into();
+        let mut buf = [0; 32];
+        let size = registry.encode(&filter, &mut buf).unwrap();
+        assert_eq!(buf[0], ONE_EURO_ID);
+
+        let (decoded, _) = registry.decode(&buf[..size]).unwrap();
+        for param in OneEuro::DESCRIPTOR.params {
+            assert_eq!(decoded.param(param.name), filter.param(param.name));
+        }
+        assert!(Registry::<u16>::new().by_name("one_euro").is_none());
+    }
+
+    #[test]
+    fn storage_size() {
+        let names = |registry: &Registry<u32, 16>| {
+            let mut names = [""; 6];
+            for (name, entry) in names.iter_mut().zip(registry.entries()) {
+                *name = entry.descriptor.name;
+            }
+            names
+        };
+        assert_eq!(
+            names(&Registry::new()),
+            ["lerp", "smooth", "fixed_lerp", "", "", ""]
+        );
+
+        assert_eq!(Registry::<u16>::new().entries().count(), 5);
+        assert_eq!(Registry::<u16, 32>::new().entries().count(), 7);
+        assert_eq!(Registry::<i32, 32>::new().entries().count(), 7);
+        let registry: Registry<i32, 24> = Registry::new();
+        assert_eq!(registry.entries().count(), 5);
+        let effects: [DynEffect<i32, 24>; 2] = [
+            Deadzone::new(i32::MIN, i32::MAX, 1000, 0).into(),
+            Expo::new(-1000, 1000, 0.3).into(),
+        ];
+        let mut buf = [0; 64];
+        let size = registry.encode_chain(&effects, &mut buf).unwrap();
+        let mut pipeline: Pipeline<i32, 2, 24> = Pipeline::new();
+        registry.decode_into(&buf[..size], &mut pipeline).unwrap();
+        assert_eq!(pipeline.get(1).unwrap().param("min"), Some(-1000.0));
+
+        // Registry and the parser work with the default storage size, without the large effects
+        let small: Registry<u32> = Registry::new();
+        let mut pipeline: Pipeline<u32> = Pipeline::new();
+        small.parse("lerp(0.5) | smooth", &mut pipeline).unwrap();
+        assert!(small.parse("deadzone", &mut pipeline).is_err());
+    }
+
+    #[test]
+    fn decode_errors() {
+        let registry: Registry = Registry::new();
+        let lerp: DynEffect = Lerp::new(0.5).into();
+        let mut buf = [0; 7];
+        let size = registry.encode(&lerp, &mut buf).unwrap();
+
+        assert_eq!(
+            registry.decode(&buf[..size - 1]).err(),
+            Some(RegistryError::UnexpectedEnd)
+        );
+        assert_eq!(
+            registry.decode(&[CUSTOM_ID, 1, 1, 0, 0, 0, 0]).err(),
+            Some(RegistryError::UnknownId(CUSTOM_ID))
+        );
+        assert_eq!(
+            registry.decode(&[LERP_ID, 1, 2]).err(),
+            Some(RegistryError::ParamCount)
+        );
+
+        buf[3..7].copy_from_slice(&2.0f32.to_le_bytes());
+        assert_eq!(
+            registry.decode(&buf).err(),
+            Some(RegistryError::Rejected(AxisError::InvalidFactor))
+        );
+        assert_eq!(
+            registry.encode(&DynEffect::new(Offset(0)), &mut buf),
+            Err(RegistryError::Unregistered)
+        );
+        // Curve points can't be stored in records
+        let curve: DynEffect = Curve::new([(0, 0), (1000, 500)]).into();
+        assert_eq!(
+            registry.encode(&curve, &mut buf),
+            Err(RegistryError::Unregistered)
+        );
+        assert_eq!(
+            registry.encode(&lerp, &mut buf[..6]),
+            Err(RegistryError::BufferTooSmall)
+        );
+
+        let mut pipeline: Pipeline<u16, 1> = Pipeline::new();
+        let mut data = [0; 14];
+        registry.encode_chain([&lerp, &lerp], &mut data).unwrap();
+        assert_eq!(
+            registry.decode_into(&data, &mut pipeline),
+            Err(RegistryError::Rejected(AxisError::PipelineFull))
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use core::ops::{Add, Sub};
+
+/// Integer sample type processed by [crate::Axis] and effects.
+/// Implemented for `u8`, `u16`, `u32`, `i16` and `i32`.
+pub trait Sample: Copy + Ord + Default + Send + Sync + 'static {
+    /// Signed accumulator for sums of up to 255 samples, `i32` for 8 and 16-bit samples and
+    /// `i64` for 32-bit ones, so that running sums of small samples stay small
+    type Sum: Copy
+        + Default
+        + Send
+        + Sync
+        + 'static
+        + From<Self>
+        + Into<i64>
+        + Add<Output = Self::Sum>
+        + Sub<Output = Self::Sum>;
+
+    const MIN: Self;
+    const MAX: Self;
+    /// [Sample::MIN] as `f32`, usable in const context
+    const MIN_F32: f32;
+    /// [Sample::MAX] as `f32`, usable in const context
+    const MAX_F32: f32;
+
+    /// Lossless conversion to `i64`
+    fn to_i64(self) -> i64;
+
+    /// Converts from `i64`, saturating at [Sample::MIN] and [Sample::MAX]
+    fn from_i64(value: i64) -> Self;
+
+    fn to_f32(self) -> f32;
+
+    /// Converts from `f32` truncating fraction, saturating at [Sample::MIN] and [Sample::MAX]
+    fn from_f32(value: f32) -> Self;
+}
+
+macro_rules! impl_sample {
+    ($($type:ty: $sum:ty),*) => {
+        $(
+            impl Sample for $type {
+                type Sum = $sum;
+
+                const MIN: Self = <$type>::MIN;
+                const MAX: Self = <$type>::MAX;
+                const MIN_F32: f32 = <$type>::MIN as f32;
+                const MAX_F32: f32 = <$type>::MAX as f32;
+
+                fn to_i64(self) -> i64 {
+                    self as i64
+                }
+
+                fn from_i64(value: i64) -> Self {
+                    value.clamp(Self::MIN as i64, Self::MAX as i64) as $type
+                }
+
+                fn to_f32(self) -> f32 {
+                    self as f32
+                }
+
+                fn from_f32(value: f32) -> Self {
+                    value as $type
+                }
+            }
+        )*
+    };
+}
+
+impl_sample!(u8: i32, u16: i32, u32: i64, i16: i32, i32: i64);
@@ -0,0 +1,571 @@
+//! serde support for [DynEffect] and [Pipeline].
+//!
+//! [DynEffect] is serialized as its descriptor name mapped to the `enabled` flag and parameters,
+//! e.g. `{"lerp": {"enabled": true, "factor": 0.5}}` in JSON. Only built-in effects of
+//! [Registry] can be serialized and deserialized, parameters missing in the data keep their
+//! defaults.
+//!
+//! [Axis] and effects are deserialized through `*Data` structs and validated with their
+//! fallible constructors, so invalid configuration is rejected instead of panicking later.
+//!
+//! Curves and splines are serialized as sequences of `(input, output)` points of any length,
+//! points not sorted by input are rejected. Spline tangents are recomputed on deserialization.
+
+use crate::{
+    AutoCalibration, Axis, AxisError, DynEffect, EffectDescriptor, Fixed, ParamDescriptor,
+    Pipeline, Registry, Rounding, Sample, effects, error::check_center, fixed,
+    registry::EffectEntry,
+};
+use core::{fmt, marker::PhantomData};
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor},
+    ser::{self, SerializeMap, SerializeSeq, SerializeTuple},
+};
+
+const ENABLED: &str = "enabled";
+
+/// `enabled` flag and parameters of the effect
+struct Params<'e, S: Sample, const N: usize> {
+    effect: &'e DynEffect<S, N>,
+    descriptor: &'static EffectDescriptor,
+}
+
+impl<S: Sample, const N: usize> Serialize for Params<'_, S, N> {
+    fn serialize<T: Serializer>(&self, serializer: T) -> Result<T::Ok, T::Error> {
+        let params = self.descriptor.params;
+        let mut map = serializer.serialize_map(Some(params.len() + 1))?;
+        map.serialize_entry(ENABLED, &self.effect.is_enabled())?;
+        for param in params {
+            let value = self.effect.param(param.name).unwrap_or(param.default);
+            map.serialize_entry(param.name, &value)?;
+        }
+        map.end()
+    }
+}
+
+impl<S: Sample, const N: usize> Serialize for DynEffect<S, N> {
+    fn serialize<T: Serializer>(&self, serializer: T) -> Result<T::Ok, T::Error> {
+        // Effects the registry can't rebuild would lose their configuration, e.g. curve points
+        let descriptor = self
+            .descriptor()
+            .filter(|descriptor| Registry::<S, N>::new().by_name(descriptor.name).is_some())
+            .ok_or_else(|| ser::Error::custom("effect is not registered"))?;
+
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(
+            descriptor.name,
+            &Params {
+                effect: self,
+                descriptor,
+            },
+        )?;
+        map.end()
+    }
+}
+
+impl<'de, S: Sample, const N: usize> Deserialize<'de> for DynEffect<S, N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(EffectVisitor(PhantomData))
+    }
+}
+
+struct EffectVisitor<S: Sample, const N: usize>(PhantomData<S>);
+
+impl<'de, S: Sample, const N: usize> Visitor<'de> for EffectVisitor<S, N> {
+    type Value = DynEffect<S, N>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "effect name mapped to its parameters")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let entry = map
+            .next_key_seed(EntrySeed(PhantomData))?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        map.next_value_seed(ParamsSeed(entry))
+    }
+}
+
+/// Looks up registry entry by effect name
+struct EntrySeed<S: Sample, const N: usize>(PhantomData<S>);
+
+impl<'de, S: Sample, const N: usize> DeserializeSeed<'de> for EntrySeed<S, N> {
+    type Value = EffectEntry<S, N>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de, S: Sample, const N: usize> Visitor<'de> for EntrySeed<S, N> {
+    type Value = EffectEntry<S, N>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "effect name")
+    }
+
+    fn visit_str<E: de::Error>(self, name: &str) -> Result<Self::Value, E> {
+        Registry::new()
+            .by_name(name)
+            .copied()
+            .ok_or_else(|| E::custom(format_args!("unknown effect `{name}`")))
+    }
+}
+
+/// Creates the effect of `entry` and applies parameters to it
+struct ParamsSeed<S: Sample, const N: usize>(EffectEntry<S, N>);
+
+impl<'de, S: Sample, const N: usize> DeserializeSeed<'de> for ParamsSeed<S, N> {
+    type Value = DynEffect<S, N>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, S: Sample, const N: usize> Visitor<'de> for ParamsSeed<S, N> {
+    type Value = DynEffect<S, N>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "parameters of `{}`", self.0.descriptor.name)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut effect = (self.0.create)();
+        while let Some(key) = map.next_key_seed(KeySeed(self.0.descriptor))? {
+            match key {
+                Key::Enabled => effect.set_enabled(map.next_value()?),
+                Key::Param(param) => effect
+                    .set_param(param.name, map.next_value()?)
+                    .map_err(de::Error::custom)?,
+            }
+        }
+        Ok(effect)
+    }
+}
+
+enum Key {
+    Enabled,
+    Param(&'static ParamDescriptor),
+}
+
+/// Resolves parameter name with effect descriptor
+struct KeySeed(&'static EffectDescriptor);
+
+impl<'de> DeserializeSeed<'de> for KeySeed {
+    type Value = Key;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de> Visitor<'de> for KeySeed {
+    type Value = Key;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "parameter name")
+    }
+
+    fn visit_str<E: de::Error>(self, name: &str) -> Result<Self::Value, E> {
+        if name == ENABLED {
+            return Ok(Key::Enabled);
+        }
+
+        self.0.param(name).map(Key::Param).ok_or_else(|| {
+            E::custom(format_args!(
+                "unknown parameter `{name}` of `{}`",
+                self.0.name
+            ))
+        })
+    }
+}
+
+impl<S: Sample, const CAP: usize, const N: usize> Serialize for Pipeline<S, CAP, N> {
+    fn serialize<T: Serializer>(&self, serializer: T) -> Result<T::Ok, T::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for effect in self {
+            seq.serialize_element(effect)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de, S: Sample, const CAP: usize, const N: usize> Deserialize<'de> for Pipeline<S, CAP, N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(PipelineVisitor(PhantomData))
+    }
+}
+
+struct PipelineVisitor<S: Sample, const CAP: usize, const N: usize>(PhantomData<S>);
+
+impl<'de, S: Sample, const CAP: usize, const N: usize> Visitor<'de> for PipelineVisitor<S, CAP, N> {
+    type Value = Pipeline<S, CAP, N>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "at most {CAP} effects")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut pipeline = Pipeline::new();
+        while let Some(effect) = seq.next_element::<DynEffect<S, N>>()? {
+            pipeline.push(effect).map_err(de::Error::custom)?;
+        }
+        Ok(pipeline)
+    }
+}
+
+fn serialize_points<S: Sample + Serialize, T: Serializer>(
+    points: &[(S, S)],
+    serializer: T,
+) -> Result<T::Ok, T::Error> {
+    let mut seq = serializer.serialize_tuple(points.len())?;
+    for point in points {
+        seq.serialize_element(point)?;
+    }
+    seq.end()
+}
+
+fn deserialize_points<'de, S: Sample + Deserialize<'de>, D: Deserializer<'de>, const N: usize>(
+    deserializer: D,
+) -> Result<[(S, S); N], D::Error> {
+    deserializer.deserialize_tuple(N, PointsVisitor(PhantomData))
+}
+
+struct PointsVisitor<S: Sample, const N: usize>(PhantomData<S>);
+
+impl<'de, S: Sample + Deserialize<'de>, const N: usize> Visitor<'de> for PointsVisitor<S, N> {
+    type Value = [(S, S); N];
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{N} points sorted by input")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut points = [(S::default(), S::default()); N];
+        for (i, point) in points.iter_mut().enumerate() {
+            *point = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+
+        if points.windows(2).any(|pair| pair[0].0 > pair[1].0) {
+            return Err(de::Error::custom("points are not sorted by input"));
+        }
+        Ok(points)
+    }
+}
+
+impl<const N: usize, S: Sample + Serialize> Serialize for effects::Curve<N, S> {
+    fn serialize<T: Serializer>(&self, serializer: T) -> Result<T::Ok, T::Error> {
+        serialize_points(self.points(), serializer)
+    }
+}
+
+impl<'de, const N: usize, S: Sample + Deserialize<'de>> Deserialize<'de> for effects::Curve<N, S> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_points(deserializer).map(Self::new)
+    }
+}
+
+impl<const N: usize, S: Sample + Serialize> Serialize for effects::Spline<N, S> {
+    fn serialize<T: Serializer>(&self, serializer: T) -> Result<T::Ok, T::Error> {
+        serialize_points(self.points(), serializer)
+    }
+}
+
+impl<'de, const N: usize, S: Sample + Deserialize<'de>> Deserialize<'de> for effects::Spline<N, S> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_points(deserializer).map(Self::from_points)
+    }
+}
+
+impl<const N: usize, S: Sample + Serialize> Serialize for fixed::Spline<N, S> {
+    fn serialize<T: Serializer>(&self, serializer: T) -> Result<T::Ok, T::Error> {
+        serialize_points(self.points(), serializer)
+    }
+}
+
+impl<'de, const N: usize, S: Sample + Deserialize<'de>> Deserialize<'de> for fixed::Spline<N, S> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_points(deserializer).map(Self::from_points)
+    }
+}
+
+/// Deserialized [Axis] configuration, validated with [crate::AxisBuilder]
+#[derive(Deserialize)]
+pub(crate) struct AxisData<S: Sample> {
+    min: S,
+    max: S,
+    center: Option<S>,
+    reversed: bool,
+    step_filter_factor: S,
+    rounding: Rounding,
+    auto_calibration: Option<AutoCalibration<S>>,
+}
+
+impl<S: Sample, const N: usize> TryFrom<AxisData<S>> for Axis<S, N> {
+    type Error = AxisError;
+
+    fn try_from(data: AxisData<S>) -> Result<Self, AxisError> {
+        let mut builder = Axis::builder(data.min, data.max)
+            .reversed(data.reversed)
+            .step_filter_factor(data.step_filter_factor)
+            .rounding(data.rounding);
+        if let Some(center) = data.center {
+            builder = builder.center(center);
+        }
+
+        let mut axis = builder.build()?.with_effect_size();
+        axis.auto_calibration = data.auto_calibration;
+        Ok(axis)
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct LerpData {
+    lerp_factor: f32,
+}
+
+impl TryFrom<LerpData> for effects::Lerp {
+    type Error = AxisError;
+
+    fn try_from(data: LerpData) -> Result<Self, AxisError> {
+        Self::try_new(data.lerp_factor)
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SmoothData<S: Sample> {
+    speed: S,
+}
+
+impl<S: Sample> TryFrom<SmoothData<S>> for effects::Smooth<S> {
+    type Error = AxisError;
+
+    fn try_from(data: SmoothData<S>) -> Result<Self, AxisError> {
+        Self::try_new(data.speed)
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct DeadzoneData<S: Sample> {
+    min: S,
+    max: S,
+    center: S,
+    inner: S,
+    outer: S,
+}
+
+impl<S: Sample> TryFrom<DeadzoneData<S>> for effects::Deadzone<S> {
+    type Error = AxisError;
+
+    fn try_from(data: DeadzoneData<S>) -> Result<Self, AxisError> {
+        let deadzone = Self::try_new(data.min, data.max, data.inner, data.outer)?;
+        check_center(data.min, data.center, data.max)?;
+        Ok(deadzone.with_center(data.center))
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ExpoData<S: Sample> {
+    min: S,
+    max: S,
+    center: S,
+    low: f32,
+    high: f32,
+}
+
+impl<S: Sample> TryFrom<ExpoData<S>> for effects::Expo<S> {
+    type Error = AxisError;
+
+    fn try_from(data: ExpoData<S>) -> Result<Self, AxisError> {
+        let expo = Self::try_asymmetric(data.min, data.max, data.low, data.high)?;
+        check_center(data.min, data.center, data.max)?;
+        Ok(expo.with_center(data.center))
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct OneEuroData {
+    min_cutoff: f32,
+    beta: f32,
+    d_cutoff: f32,
+    sample_period: f32,
+}
+
+impl TryFrom<OneEuroData> for effects::OneEuro {
+    type Error = AxisError;
+
+    fn try_from(data: OneEuroData) -> Result<Self, AxisError> {
+        Self::new(
+            data.sample_period,
+            data.min_cutoff,
+            data.beta,
+            data.d_cutoff,
+        )
+    }
+}
+
+/// Factors of fixed-point effects are stored as [Fixed] bits
+#[derive(Deserialize)]
+pub(crate) struct FixedLerpData {
+    lerp_factor: i32,
+}
+
+impl TryFrom<FixedLerpData> for fixed::Lerp {
+    type Error = AxisError;
+
+    fn try_from(data: FixedLerpData) -> Result<Self, AxisError> {
+        Self::try_new(Fixed::from_bits(data.lerp_factor))
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct FixedExpoData<S: Sample> {
+    min: S,
+    max: S,
+    center: S,
+    low: i32,
+    high: i32,
+}
+
+impl<S: Sample> TryFrom<FixedExpoData<S>> for fixed::Expo<S> {
+    type Error = AxisError;
+
+    fn try_from(data: FixedExpoData<S>) -> Result<Self, AxisError> {
+        let low = Fixed::from_bits(data.low);
+        let high = Fixed::from_bits(data.high);
+        let expo = Self::try_asymmetric(data.min, data.max, low, high)?;
+        check_center(data.min, data.center, data.max)?;
+        Ok(expo.with_center(data.center))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use super::*;
+    use crate::{
+        PipelineAxis,
+        effects::{Curve, Deadzone, Expo, Lerp, Median, Spline},
+    };
+
+    #[test]
+    fn effect_json() {
+        let mut effect: DynEffect = Deadzone::new(0, 1000, 10, 0).into();
+        effect.set_enabled(false);
+        let json = serde_json::to_string(&effect).unwrap();
+        assert_eq!(
+            json,
+            r#"{"deadzone":{"enabled":false,"inner":10.0,"outer":0.0,"center":500.0,"min":0.0,"max":1000.0}}"#
+        );
+
+        let decoded: DynEffect = serde_json::from_str(&json).unwrap();
+        assert!(!decoded.is_enabled());
+        assert_eq!(decoded.param("center"), Some(500.0));
+
+        let lerp: DynEffect = serde_json::from_str(r#"{"lerp":{"factor":0.25}}"#).unwrap();
+        assert_eq!(lerp.param("factor"), Some(0.25));
+        assert!(lerp.is_enabled());
+
+        assert!(serde_json::from_str::<DynEffect>(r#"{"blur":{}}"#).is_err());
+        let curve: DynEffect = Curve::new([(0, 0), (1000, 500)]).into();
+        assert!(serde_json::to_string(&curve).is_err());
+        let median: DynEffect = Median::<3>::new().into();
+        assert!(serde_json::to_string(&median).is_err());
+        assert!(serde_json::from_str::<DynEffect>(r#"{"lerp":{"gain":1.0}}"#).is_err());
+        assert!(serde_json::from_str::<DynEffect>(r#"{"lerp":{"factor":2.0}}"#).is_err());
+    }
+
+    #[test]
+    fn runtime_state_is_skipped() {
+        let mut axis = Axis::new(0, 1000, false);
+        axis.update(700, []);
+        let json = serde_json::to_string(&axis).unwrap();
+        assert_eq!(
+            json,
+            r#"{"min":0,"max":1000,"center":null,"reversed":false,"step_filter_factor":0,"rounding":"Floor","auto_calibration":null}"#
+        );
+
+        let mut lerp = Lerp::new(0.5);
+        crate::Effect::<u16>::update(&mut lerp, 100);
+        assert_eq!(
+            serde_json::to_string(&lerp).unwrap(),
+            r#"{"lerp_factor":0.5}"#
+        );
+    }
+
+    #[test]
+    fn invalid_config_is_rejected() {
+        let axis = r#"{"min":10,"max":0,"center":null,"reversed":false,"step_filter_factor":0,"rounding":"Floor","auto_calibration":null}"#;
+        assert!(serde_json::from_str::<Axis>(axis).is_err());
+        let axis = r#"{"min":0,"max":100,"center":200,"reversed":false,"step_filter_factor":0,"rounding":"Floor","auto_calibration":null}"#;
+        assert!(serde_json::from_str::<Axis>(axis).is_err());
+
+        let deadzone = r#"{"min":1000,"max":0,"center":500,"inner":10,"outer":0}"#;
+        assert!(serde_json::from_str::<Deadzone>(deadzone).is_err());
+        let expo = r#"{"min":0,"max":1000,"center":2000,"low":0.2,"high":0.2}"#;
+        assert!(serde_json::from_str::<Expo>(expo).is_err());
+        let expo = r#"{"min":0,"max":1000,"center":500,"low":3.0,"high":0.2}"#;
+        assert!(serde_json::from_str::<Expo>(expo).is_err());
+        assert!(serde_json::from_str::<Lerp>(r#"{"lerp_factor":2.0}"#).is_err());
+
+        let expo = r#"{"min":0,"max":1000,"center":400,"low":0.2,"high":-0.2}"#;
+        let mut expo: Expo = serde_json::from_str(expo).unwrap();
+        assert_eq!(crate::Effect::<u16>::param(&expo, "center"), Some(400.0));
+        assert_eq!(crate::Effect::<u16>::update(&mut expo, 400), 400);
+    }
+
+    #[test]
+    fn curve_points() {
+        let curve = Curve::new([(0u16, 0), (100, 10), (200, 40), (1000, 1000)]);
+        let json = serde_json::to_string(&curve).unwrap();
+        assert_eq!(json, "[[0,0],[100,10],[200,40],[1000,1000]]");
+        let decoded: Curve<4> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.points(), curve.points());
+
+        // Longer than the 32 elements serde supports for arrays
+        let points: [(i32, i32); 40] = core::array::from_fn(|i| (i as i32 * 10, -(i as i32)));
+        let curve = Curve::new(points);
+        let mut buf = [0; 256];
+        let bytes = postcard::to_slice(&curve, &mut buf).unwrap();
+        let decoded: Curve<40, i32> = postcard::from_bytes(bytes).unwrap();
+        assert_eq!(decoded.points(), curve.points());
+
+        let spline = Spline::new([(0, 0), (500, 100), (1000, 1000)]);
+        let json = serde_json::to_string(&spline).unwrap();
+        let decoded: Spline<3> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.evaluate(700), spline.evaluate(700));
+        let bytes = postcard::to_slice(&spline, &mut buf).unwrap();
+        let decoded: fixed::Spline<3> = postcard::from_bytes(bytes).unwrap();
+        assert_eq!(decoded.points(), spline.points());
+
+        assert!(serde_json::from_str::<Curve<2>>("[[0,0],[100,10],[200,40]]").is_err());
+        assert!(serde_json::from_str::<Curve<3>>("[[0,0],[100,10]]").is_err());
+        assert!(serde_json::from_str::<Curve<2>>("[[100,0],[0,10]]").is_err());
+    }
+
+    #[test]
+    fn pipeline_axis_postcard() {
+        let mut axis: PipelineAxis = PipelineAxis::new(Axis::new_centered(0, 480, 1000, true));
+        axis.push(Deadzone::new(0, 1000, 20, 5)).unwrap();
+        axis.push(Expo::asymmetric(0, 1000, 0.3, -0.2)).unwrap();
+        axis.push(Lerp::new(0.5)).unwrap();
+        axis.step_filter_factor = 2;
+
+        let mut buf = [0; 256];
+        let bytes = postcard::to_slice(&axis, &mut buf).unwrap();
+        let mut decoded: PipelineAxis = postcard::from_bytes(bytes).unwrap();
+        assert_eq!(decoded.pipeline().len(), 3);
+        assert_eq!(decoded.center, Some(480));
+
+        for value in (0..=1000).step_by(40) {
+            axis.update(value);
+            decoded.update(value);
+            assert_eq!(axis.output(0, 1000), decoded.output(0, 1000));
+        }
+    }
+}
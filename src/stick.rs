@@ -0,0 +1,185 @@
+use crate::{
+    Axis, EffectChain, ErasedEffect, MAX_EFFECT_SIZE, Sample, Storage, Vtable, effects::expo_curve,
+    erased_mut, map_position,
+};
+use micromath::F32Ext;
+
+/// 2D effect trait, processes stick vector with components normalized to `-1.0..=1.0`.
+/// Custom [StickEffect] has the same size limits as [crate::Effect]
+pub trait StickEffect {
+    fn update(&mut self, x: f32, y: f32) -> (f32, f32);
+}
+
+/// Dynamic dispatching wrapper for [StickEffect] trait, stores the effect inline in `N` bytes.
+#[derive(Clone)]
+pub struct DynStickEffect<const N: usize = MAX_EFFECT_SIZE>(ErasedEffect<(f32, f32), (), N>);
+
+impl<const N: usize> DynStickEffect<N> {
+    pub fn new<T: StickEffect + Clone + Send + Sync + 'static>(effect: T) -> Self {
+        fn call<T: StickEffect, const N: usize>(
+            data: &mut Storage<N>,
+            (x, y): (f32, f32),
+        ) -> (f32, f32) {
+            unsafe { erased_mut::<T, N>(data).update(x, y) }
+        }
+
+        // Safety: the vtable is created for `T`
+        Self(unsafe { ErasedEffect::new(effect, const { &Vtable::new::<T>(call::<T, N>, ()) }) })
+    }
+
+    pub fn update(&mut self, x: f32, y: f32) -> (f32, f32) {
+        self.0.update((x, y))
+    }
+}
+
+/// Two-dimensional stick. Processes X and Y axes as a combined vector, so deadzone and
+/// response curve are circular instead of square. `N` is the storage size of effects in the chain.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stick<S: Sample = u16, const N: usize = MAX_EFFECT_SIZE> {
+    pub x: Axis<S, N>,
+    pub y: Axis<S, N>,
+    /// Radial deadzone, fraction of full deflection
+    pub deadzone: f32,
+    /// Radial saturation zone at the edge, fraction of full deflection
+    pub saturation: f32,
+    /// Radial expo factor in `-1.0..=1.0`, see [crate::effects::Expo]
+    pub expo: f32,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    position: (f32, f32),
+}
+
+impl<'a, S: Sample, const N: usize> Stick<S, N> {
+    pub fn new(x: Axis<S, N>, y: Axis<S, N>) -> Self {
+        Self {
+            x,
+            y,
+            deadzone: 0.0,
+            saturation: 0.0,
+            expo: 0.0,
+            position: (0.0, 0.0),
+        }
+    }
+
+    /// Applies radial deadzone, expo and magnitude clamping
+    fn radial(&self, x: f32, y: f32) -> (f32, f32) {
+        let magnitude = (x * x + y * y).sqrt();
+        if magnitude <= self.deadzone || magnitude == 0.0 {
+            return (0.0, 0.0);
+        }
+
+        let travel = (1.0 - self.deadzone - self.saturation).max(f32::EPSILON);
+        let scaled = ((magnitude - self.deadzone) / travel).min(1.0);
+        let scale = expo_curve(scaled, self.expo.clamp(-1.0, 1.0)) / magnitude;
+        (x * scale, y * scale)
+    }
+
+    /// Updates X and Y axes with their own effect chains, then applies radial processing and
+    /// `chain` of stick effects to the combined vector
+    pub fn update<X, Y, I>(&mut self, x: S, y: S, x_chain: X, y_chain: Y, chain: I)
+    where
+        X: EffectChain<S, N>,
+        Y: EffectChain<S, N>,
+        I: IntoIterator<Item = &'a mut DynStickEffect<N>>,
+    {
+        self.x.update(x, x_chain);
+        self.y.update(y, y_chain);
+
+        let (mut x, mut y) = self.radial(self.x.output_normalized(), self.y.output_normalized());
+        for filter in chain {
+            (x, y) = filter.update(x, y);
+        }
+        self.position = (x, y);
+    }
+
+    /// Processed vector with components in `-1.0..=1.0` range
+    pub fn position(&self) -> (f32, f32) {
+        self.position
+    }
+
+    /// Maps the position to `range_min..=range_max`, see [Axis::output]
+    pub fn output(&self, range_min: u16, range_max: u16) -> (u16, u16) {
+        self.output_in(range_min, range_max)
+    }
+
+    /// Maps the position to `range_min..=range_max` range of any [Sample] type, rounded
+    /// with [Axis::rounding] of each axis, see [Axis::output_in]
+    pub fn output_in<T: Sample>(&self, range_min: T, range_max: T) -> (T, T) {
+        let (x, y) = self.position;
+        (
+            map_position(x.clamp(-1.0, 1.0), range_min, range_max, self.x.rounding),
+            map_position(y.clamp(-1.0, 1.0), range_min, range_max, self.y.rounding),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Chain, DynEffect, Rounding, effects::Lerp};
+
+    #[derive(Clone)]
+    struct Swap;
+
+    impl StickEffect for Swap {
+        fn update(&mut self, x: f32, y: f32) -> (f32, f32) {
+            (y, x)
+        }
+    }
+
+    #[test]
+    fn radial_clamp() {
+        let mut stick = Stick::new(Axis::new(0, 200, false), Axis::new(0, 200, false));
+        stick.update(200, 200, [], [], []);
+        assert_eq!(stick.output(0, 200), (170, 170));
+        stick.update(200, 100, [], [], []);
+        assert_eq!(stick.output(0, 200), (200, 100));
+    }
+
+    #[test]
+    fn radial_deadzone() {
+        let mut stick = Stick::new(
+            Axis::new_centered(0, 90, 200, false),
+            Axis::new_centered(0, 110, 200, false),
+        );
+        stick.deadzone = 0.2;
+
+        stick.update(100, 100, [], [], []);
+        assert_eq!(stick.output(0, 200), (100, 100));
+        stick.update(200, 110, [], [], []);
+        assert_eq!(stick.output(0, 200), (200, 100));
+        stick.update(90, 165, [], [], []);
+        assert_eq!(stick.output(0, 200), (100, 151));
+    }
+
+    #[test]
+    fn stick_chain() {
+        let mut stick = Stick::new(Axis::new(0, 200, false), Axis::new(0, 200, false));
+        let mut effects = [DynStickEffect::new(Swap)];
+        stick.update(200, 100, [], [], effects.iter_mut());
+        assert_eq!(stick.output(0, 200), (100, 200));
+    }
+
+    #[test]
+    fn axis_chains() {
+        let mut stick = Stick::new(Axis::new(0, 200, false), Axis::new(0, 200, false));
+        let mut x_chain = Chain::new((Lerp::new(0.5),));
+        let mut y_chain = [DynEffect::from(Lerp::new(0.25))];
+        stick.update(0, 0, &mut x_chain, &mut y_chain, []);
+        stick.update(200, 200, &mut x_chain, &mut y_chain, []);
+        assert_eq!(stick.output(0, 200), (100, 50));
+    }
+
+    #[test]
+    fn generic_output() {
+        let mut stick = Stick::new(Axis::new(0, 200, false), Axis::new(0, 200, false));
+        stick.update(200, 100, [], [], []);
+        assert_eq!(stick.output_in(-100i16, 100), (100, 0));
+        assert_eq!(stick.output_in(0u32, 1), (1, 0));
+
+        stick.update(133, 67, [], [], []);
+        assert_eq!(stick.output(0, 100), (66, 33));
+        stick.x.rounding = Rounding::Nearest;
+        stick.y.rounding = Rounding::Ceil;
+        assert_eq!(stick.output(0, 100), (67, 34));
+    }
+}